@@ -0,0 +1,26 @@
+-- Section catalog: built-in sections (user_id null) plus user-defined sections.
+-- Keys in newsletter_config.features must match a section visible to the owner.
+create table if not exists public.newsletter_section (
+  id uuid primary key default gen_random_uuid(),
+  key text not null check (key ~ '^[a-z0-9_]{1,64}$'),
+  user_id uuid references auth.users(id) on delete cascade,
+  name text not null,
+  description text not null default '',
+  default_instructions text not null default '',
+  sort_order integer not null default 0,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now()
+);
+
+create unique index if not exists newsletter_section_builtin_key on public.newsletter_section(key) where user_id is null;
+create unique index if not exists newsletter_section_user_key on public.newsletter_section(user_id, key) where user_id is not null;
+
+insert into public.newsletter_section (key, user_id, name, description, default_instructions, sort_order) values
+  ('kpis', null, 'KPIs', 'Key performance indicators: metrics, trends, and how they compare to targets or prior period.', 'Highlight the most important metrics, their trend and any outliers.', 10),
+  ('competitor_analysis', null, 'Competitor analysis', 'Summary of what competitors are doing and how they compare.', 'Summarize notable competitor moves and how they compare.', 20),
+  ('market_segment_summary', null, 'Market segment summary', 'Overview of market segments, size, and how they are changing.', 'Give an overview of the main market segments and how they are changing.', 30),
+  ('identify_risks', null, 'Identify risks', 'Risks and uncertainties that could affect your business or market.', 'List the main risks and uncertainties, most likely first.', 40)
+on conflict do nothing;
+
+-- Only backend (service role) writes; built-ins are managed by migrations.
+alter table public.newsletter_section enable row level security;
@@ -0,0 +1,36 @@
+-- Versioned prompt templates. Each row is an immutable version; the highest version is the default.
+-- Placeholders: {title}, {topics}, {tone}, {length} in header_template; {name}, {instructions} in section templates.
+create table if not exists public.prompt_template (
+  id uuid primary key default gen_random_uuid(),
+  version integer not null unique check (version >= 1),
+  system_instructions text not null,
+  header_template text not null,
+  section_template text not null,
+  section_instructions_template text not null,
+  section_snippets jsonb not null default '{}',
+  tone_guidance jsonb not null default '{}',
+  length_guidance jsonb not null default '{}',
+  notes text not null default '',
+  created_at timestamptz not null default now()
+);
+
+insert into public.prompt_template (
+  version, system_instructions, header_template, section_template, section_instructions_template,
+  section_snippets, tone_guidance, length_guidance, notes
+) values (
+  1,
+  'You are a newsletter writer. Produce a single newsletter document. Include only the sections the user requested. Follow their per-section instructions. Output plain text or markdown suitable for email.',
+  E'Write a daily newsletter with title: {title}\nTopics: {topics}\nTone: {tone}\nLength: {length}',
+  'Include a section: {name}.',
+  ' User instructions for this section: {instructions}',
+  '{}',
+  '{}',
+  '{}',
+  'Initial version (previously compiled into the backend).'
+) on conflict (version) do nothing;
+
+-- Newsletters may pin a version (null = latest); runs record the version used.
+alter table public.newsletter_config add column if not exists prompt_template_version integer references public.prompt_template(version);
+alter table public.newsletter_run_log add column if not exists prompt_template_version integer;
+
+alter table public.prompt_template enable row level security;
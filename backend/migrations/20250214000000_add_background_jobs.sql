@@ -0,0 +1,17 @@
+-- Background jobs executed by the in-process worker pool (sample sends and scheduled runs).
+create table if not exists public.background_job (
+  id uuid primary key,
+  user_id uuid not null references auth.users(id) on delete cascade,
+  kind text not null check (kind in ('send_sample', 'scheduled_run')),
+  newsletter_config_id uuid references public.newsletter_config(id) on delete set null,
+  status text not null default 'queued' check (status in ('queued', 'running', 'succeeded', 'failed')),
+  error_message text,
+  created_at timestamptz not null default now(),
+  started_at timestamptz,
+  finished_at timestamptz
+);
+
+create index if not exists background_job_user_id on public.background_job(user_id);
+create index if not exists background_job_status on public.background_job(status) where status in ('queued', 'running');
+
+alter table public.background_job enable row level security;
@@ -0,0 +1,29 @@
+-- Self-service access requests. Approving one inserts the user into approved_users.
+create table if not exists public.access_request (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references auth.users(id) on delete cascade,
+  email text not null,
+  message text not null default '',
+  status text not null default 'pending' check (status in ('pending', 'approved', 'rejected')),
+  reviewed_by uuid references auth.users(id) on delete set null,
+  reviewed_at timestamptz,
+  created_at timestamptz not null default now()
+);
+
+create index if not exists access_request_status on public.access_request(status, created_at);
+-- At most one open request per user.
+create unique index if not exists access_request_one_pending on public.access_request(user_id) where status = 'pending';
+
+-- Roles granted to users. Insert ('<uuid>', 'admin') to make someone an admin.
+create table if not exists public.user_roles (
+  user_id uuid not null references auth.users(id) on delete cascade,
+  role text not null check (role in ('admin')),
+  created_at timestamptz not null default now(),
+  primary key (user_id, role)
+);
+
+alter table public.access_request enable row level security;
+alter table public.user_roles enable row level security;
+
+alter table public.approved_users add column if not exists approved_at timestamptz not null default now();
+alter table public.approved_users add column if not exists approved_by uuid references auth.users(id) on delete set null;
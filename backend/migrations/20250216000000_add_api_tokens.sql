@@ -0,0 +1,18 @@
+-- Personal API tokens for scripted access. Only a SHA-256 hash of the token is stored;
+-- token_prefix is kept so users can tell their tokens apart.
+create table if not exists public.api_token (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references auth.users(id) on delete cascade,
+  name text not null,
+  token_hash text not null unique,
+  token_prefix text not null,
+  scopes text[] not null default '{read}' check (scopes <@ array['read', 'write']::text[] and cardinality(scopes) > 0),
+  expires_at timestamptz,
+  last_used_at timestamptz,
+  revoked_at timestamptz,
+  created_at timestamptz not null default now()
+);
+
+create index if not exists api_token_user_id on public.api_token(user_id);
+
+alter table public.api_token enable row level security;
@@ -0,0 +1,23 @@
+-- Newsletters the scheduler should run at `as_of`: active, send_time_utc within the `window_minutes`
+-- up to as_of (UTC time of day), and no run logged yet that UTC day. Replaces a last-run lookup per
+-- newsletter with one call per tick (PostgREST: POST /rest/v1/rpc/due_newsletter_configs).
+create or replace function public.due_newsletter_configs(as_of timestamptz, window_minutes integer)
+returns setof public.newsletter_config
+language sql
+stable
+as $$
+  select c.*
+  from public.newsletter_config c
+  where c.is_active
+    and extract(hour from c.send_time_utc) * 60 + extract(minute from c.send_time_utc)
+      between extract(hour from as_of at time zone 'UTC') * 60 + extract(minute from as_of at time zone 'UTC') - window_minutes + 1
+          and extract(hour from as_of at time zone 'UTC') * 60 + extract(minute from as_of at time zone 'UTC')
+    and not exists (
+      select 1
+      from public.newsletter_run_log l
+      where l.newsletter_config_id = c.id
+        and l.run_at >= date_trunc('day', as_of at time zone 'UTC') at time zone 'UTC'
+    )
+$$;
+
+create index if not exists newsletter_run_log_config_run_at on public.newsletter_run_log(newsletter_config_id, run_at);
@@ -0,0 +1,9 @@
+-- Free-form labels for organising newsletters; GET /api/me/newsletters?tag= filters on them.
+alter table public.newsletter_config add column if not exists tags text[] not null default '{}';
+
+create index if not exists newsletter_config_tags on public.newsletter_config using gin (tags);
+
+-- Keyset pagination of a user's list: one index per sort, each ending on id.
+create index if not exists newsletter_config_user_created on public.newsletter_config(user_id, created_at, id);
+create index if not exists newsletter_config_user_title on public.newsletter_config(user_id, title, id);
+create index if not exists newsletter_config_user_send_time on public.newsletter_config(user_id, send_time_utc, id);
@@ -0,0 +1,22 @@
+-- Preset gallery: starting points a newsletter_config can be created from. Curated presets are
+-- published by admins; the rest by any approved user. Everyone can see every preset.
+-- Per-section prompt instructions live in features as custom_request, as in newsletter_config.
+create table if not exists public.newsletter_preset (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid references auth.users(id) on delete set null,
+  curated boolean not null default false,
+  name text not null,
+  description text not null default '',
+  topics text[] not null default '{}',
+  tone text not null default 'neutral',
+  length text not null default 'medium' check (length in ('short', 'medium', 'long')),
+  features jsonb not null default '{}',
+  prompt_template_version integer references public.prompt_template(version),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now()
+);
+
+create index if not exists newsletter_preset_user_id on public.newsletter_preset(user_id);
+
+-- Only backend (service role) reads and writes.
+alter table public.newsletter_preset enable row level security;
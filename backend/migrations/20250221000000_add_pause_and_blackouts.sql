@@ -0,0 +1,8 @@
+-- Vacation mode: scheduled issues are skipped until paused_until, and on blackout days
+-- (JSON array of {start, end, repeat?, until?, reason}). Skipped days get a 'skipped' run log.
+alter table public.newsletter_config add column if not exists paused_until timestamptz;
+alter table public.newsletter_config add column if not exists blackouts jsonb not null default '[]';
+
+alter table public.newsletter_run_log drop constraint if exists newsletter_run_log_status_check;
+alter table public.newsletter_run_log add constraint newsletter_run_log_status_check
+  check (status in ('success', 'failure', 'skipped'));
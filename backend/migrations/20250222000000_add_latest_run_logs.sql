@@ -0,0 +1,13 @@
+-- The most recent run log of each of the given newsletters, for showing last run status and the
+-- next send in newsletter listings without a lookup per newsletter
+-- (PostgREST: POST /rest/v1/rpc/latest_run_logs).
+create or replace function public.latest_run_logs(config_ids uuid[])
+returns setof public.newsletter_run_log
+language sql
+stable
+as $$
+  select distinct on (l.newsletter_config_id) l.*
+  from public.newsletter_run_log l
+  where l.newsletter_config_id = any(config_ids)
+  order by l.newsletter_config_id, l.run_at desc
+$$;
@@ -0,0 +1,13 @@
+-- Re-asserts approved_users and its service-role-only policy without failing when they exist,
+-- so a database first set up by hand (e.g. from the dashboard) ends in the same state.
+create table if not exists public.approved_users (
+  user_id uuid primary key
+);
+
+alter table public.approved_users enable row level security;
+
+drop policy if exists "service_role_only" on public.approved_users;
+create policy "service_role_only" on public.approved_users
+  for all
+  using (false)
+  with check (false);
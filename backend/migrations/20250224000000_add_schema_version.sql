@@ -0,0 +1,33 @@
+-- Schema version for backends that only reach the database over PostgREST (STORAGE_BACKEND=supabase),
+-- which cannot read _sqlx_migrations (PostgREST: POST /rest/v1/rpc/schema_version). It is the
+-- newest version recorded by `speedo-backend migrate`, or 0 when nothing is recorded, so later
+-- migrations never need to touch it.
+create or replace function public.schema_version()
+returns bigint
+language plpgsql
+stable
+security definer
+set search_path = public
+as $$
+declare
+  recorded bigint;
+begin
+  if to_regclass('public._sqlx_migrations') is not null then
+    select max(version) into recorded from public._sqlx_migrations where success;
+  end if;
+  return coalesce(recorded, 0);
+end;
+$$;
+
+-- Only the backend (service role) may call it; the Supabase roles do not exist on plain Postgres.
+revoke execute on function public.schema_version() from public;
+do $$
+begin
+  if exists (select 1 from pg_roles where rolname = 'anon') then
+    revoke execute on function public.schema_version() from anon, authenticated;
+  end if;
+  if exists (select 1 from pg_roles where rolname = 'service_role') then
+    grant execute on function public.schema_version() to service_role;
+  end if;
+end
+$$;
@@ -0,0 +1,7 @@
+-- Which backend instance queued a job, so a restarting instance only fails its own unfinished
+-- jobs and leaves those of other live instances sharing the database alone.
+alter table public.background_job add column if not exists instance_id text;
+
+drop index if exists public.background_job_status;
+create index if not exists background_job_instance_status on public.background_job(instance_id, status)
+  where status in ('queued', 'running');
@@ -0,0 +1,7 @@
+-- The UTC day whose issue a scheduled run sends. At most one run per newsletter and day may be
+-- queued, running or succeeded, so instances sharing the database cannot send the same issue
+-- twice; a failed run frees the day for a retry.
+alter table public.background_job add column if not exists slot date;
+
+create unique index if not exists background_job_config_slot on public.background_job(newsletter_config_id, slot)
+  where slot is not null and status <> 'failed';
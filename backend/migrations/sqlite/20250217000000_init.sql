@@ -0,0 +1,135 @@
+-- SQLite schema for single-box / offline deployments (STORAGE_BACKEND=sqlite).
+-- Mirrors the Supabase migrations: uuids are 16-byte blobs, timestamps RFC 3339 text,
+-- text[] and jsonb columns JSON text. There is no auth schema, so user ids are not foreign keys.
+create table if not exists newsletter_config (
+  id blob primary key,
+  user_id blob not null,
+  title text not null default '',
+  topics text not null default '[]',
+  tone text not null default 'neutral',
+  length text not null default 'medium' check (length in ('short', 'medium', 'long')),
+  send_time_utc text not null default '09:00:00',
+  timezone text not null default 'UTC',
+  delivery_email text not null,
+  is_active integer not null default 1,
+  features text not null default '{}',
+  prompt_template_version integer,
+  created_at text not null,
+  updated_at text not null
+);
+
+create index if not exists newsletter_config_user_id on newsletter_config(user_id);
+create index if not exists newsletter_config_is_active on newsletter_config(is_active);
+
+create table if not exists newsletter_run_log (
+  id blob primary key,
+  newsletter_config_id blob not null references newsletter_config(id) on delete cascade,
+  run_at text not null,
+  status text not null check (status in ('success', 'failure')),
+  error_message text,
+  openclaw_response_id text,
+  prompt_template_version integer
+);
+
+create index if not exists newsletter_run_log_config_run_at on newsletter_run_log(newsletter_config_id, run_at);
+
+create table if not exists approved_users (
+  user_id blob primary key,
+  approved_at text not null,
+  approved_by blob
+);
+
+-- Insert (x'<uuid hex>', 'admin') to make someone an admin.
+create table if not exists user_roles (
+  user_id blob not null,
+  role text not null check (role in ('admin')),
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+  primary key (user_id, role)
+);
+
+create table if not exists access_request (
+  id blob primary key,
+  user_id blob not null,
+  email text not null,
+  message text not null default '',
+  status text not null default 'pending' check (status in ('pending', 'approved', 'rejected')),
+  reviewed_by blob,
+  reviewed_at text,
+  created_at text not null
+);
+
+create unique index if not exists access_request_one_pending on access_request(user_id) where status = 'pending';
+
+create table if not exists newsletter_section (
+  id blob primary key,
+  key text not null,
+  user_id blob,
+  name text not null,
+  description text not null default '',
+  default_instructions text not null default '',
+  sort_order integer not null default 0,
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+  updated_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+create unique index if not exists newsletter_section_builtin_key on newsletter_section(key) where user_id is null;
+create unique index if not exists newsletter_section_user_key on newsletter_section(user_id, key) where user_id is not null;
+
+insert or ignore into newsletter_section (id, key, user_id, name, description, default_instructions, sort_order) values
+  (randomblob(16), 'kpis', null, 'KPIs', 'Key performance indicators: metrics, trends, and how they compare to targets or prior period.', 'Highlight the most important metrics, their trend and any outliers.', 10),
+  (randomblob(16), 'competitor_analysis', null, 'Competitor analysis', 'Summary of what competitors are doing and how they compare.', 'Summarize notable competitor moves and how they compare.', 20),
+  (randomblob(16), 'market_segment_summary', null, 'Market segment summary', 'Overview of market segments, size, and how they are changing.', 'Give an overview of the main market segments and how they are changing.', 30),
+  (randomblob(16), 'identify_risks', null, 'Identify risks', 'Risks and uncertainties that could affect your business or market.', 'List the main risks and uncertainties, most likely first.', 40);
+
+create table if not exists prompt_template (
+  version integer primary key check (version >= 1),
+  system_instructions text not null,
+  header_template text not null,
+  section_template text not null,
+  section_instructions_template text not null,
+  section_snippets text not null default '{}',
+  tone_guidance text not null default '{}',
+  length_guidance text not null default '{}',
+  notes text not null default '',
+  created_at text not null default (strftime('%Y-%m-%dT%H:%M:%fZ', 'now'))
+);
+
+insert or ignore into prompt_template (
+  version, system_instructions, header_template, section_template, section_instructions_template, notes
+) values (
+  1,
+  'You are a newsletter writer. Produce a single newsletter document. Include only the sections the user requested. Follow their per-section instructions. Output plain text or markdown suitable for email.',
+  'Write a daily newsletter with title: {title}' || char(10) || 'Topics: {topics}' || char(10) || 'Tone: {tone}' || char(10) || 'Length: {length}',
+  'Include a section: {name}.',
+  ' User instructions for this section: {instructions}',
+  'Initial version (previously compiled into the backend).'
+);
+
+create table if not exists background_job (
+  id blob primary key,
+  user_id blob not null,
+  kind text not null check (kind in ('send_sample', 'scheduled_run')),
+  newsletter_config_id blob references newsletter_config(id) on delete set null,
+  status text not null default 'queued' check (status in ('queued', 'running', 'succeeded', 'failed')),
+  error_message text,
+  created_at text not null,
+  started_at text,
+  finished_at text
+);
+
+create index if not exists background_job_user_id on background_job(user_id);
+
+create table if not exists api_token (
+  id blob primary key,
+  user_id blob not null,
+  name text not null,
+  token_hash text not null unique,
+  token_prefix text not null,
+  scopes text not null default '["read"]',
+  expires_at text,
+  last_used_at text,
+  revoked_at text,
+  created_at text not null
+);
+
+create index if not exists api_token_user_id on api_token(user_id);
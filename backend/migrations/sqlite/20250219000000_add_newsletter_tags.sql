@@ -0,0 +1,6 @@
+-- Mirrors the Postgres migration of the same version; tags is a JSON array like topics.
+alter table newsletter_config add column tags text not null default '[]';
+
+create index if not exists newsletter_config_user_created on newsletter_config(user_id, created_at, id);
+create index if not exists newsletter_config_user_title on newsletter_config(user_id, title, id);
+create index if not exists newsletter_config_user_send_time on newsletter_config(user_id, send_time_utc, id);
@@ -0,0 +1,17 @@
+-- Mirrors the Postgres migration of the same version.
+create table if not exists newsletter_preset (
+  id blob primary key,
+  user_id blob,
+  curated integer not null default 0,
+  name text not null,
+  description text not null default '',
+  topics text not null default '[]',
+  tone text not null default 'neutral',
+  length text not null default 'medium' check (length in ('short', 'medium', 'long')),
+  features text not null default '{}',
+  prompt_template_version integer,
+  created_at text not null,
+  updated_at text not null
+);
+
+create index if not exists newsletter_preset_user_id on newsletter_preset(user_id);
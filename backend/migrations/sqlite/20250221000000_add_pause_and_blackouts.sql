@@ -0,0 +1,24 @@
+-- Mirrors the Postgres migration of the same version. SQLite cannot change a check constraint,
+-- so newsletter_run_log is rebuilt to allow 'skipped'.
+alter table newsletter_config add column paused_until text;
+alter table newsletter_config add column blackouts text not null default '[]';
+
+create table newsletter_run_log_new (
+  id blob primary key,
+  newsletter_config_id blob not null references newsletter_config(id) on delete cascade,
+  run_at text not null,
+  status text not null check (status in ('success', 'failure', 'skipped')),
+  error_message text,
+  openclaw_response_id text,
+  prompt_template_version integer
+);
+
+insert into newsletter_run_log_new (id, newsletter_config_id, run_at, status, error_message, openclaw_response_id,
+  prompt_template_version)
+select id, newsletter_config_id, run_at, status, error_message, openclaw_response_id, prompt_template_version
+from newsletter_run_log;
+
+drop table newsletter_run_log;
+alter table newsletter_run_log_new rename to newsletter_run_log;
+
+create index if not exists newsletter_run_log_config_run_at on newsletter_run_log(newsletter_config_id, run_at);
@@ -0,0 +1,2 @@
+-- Mirrors the Postgres migration of the same version: the instance that queued each job.
+alter table background_job add column instance_id text;
@@ -0,0 +1,5 @@
+-- Mirrors the Postgres migration of the same version: one unfailed scheduled run per newsletter and day.
+alter table background_job add column slot text;
+
+create unique index if not exists background_job_config_slot on background_job(newsletter_config_id, slot)
+  where slot is not null and status <> 'failed';
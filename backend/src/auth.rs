@@ -1,15 +1,23 @@
-use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::jwk::{Jwk, JwkSet};
 use jsonwebtoken::{decode, decode_header, DecodingKey, Validation};
 use rocket::request::Outcome;
 use rocket::request::{FromRequest, Request};
 use rocket::serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserContext {
     pub user_id: Uuid,
     pub email: Option<String>,
+    /// Roles from the JWT's `app_metadata` (`role` and/or `roles`), set by the service role.
+    #[serde(default)]
+    pub roles: Vec<String>,
+    /// Scopes of the personal API token used; None for a Supabase session.
+    #[serde(default)]
+    pub token_scopes: Option<Vec<String>>,
 }
 
 pub struct User(pub UserContext);
@@ -37,6 +45,28 @@ struct SupabaseJwtClaims {
     email: Option<String>,
     #[serde(default, deserialize_with = "deserialize_aud")]
     aud: Option<String>,
+    #[serde(default)]
+    app_metadata: AppMetadata,
+}
+
+/// Users cannot edit their own app_metadata (only the service role can), so roles here are trusted.
+#[derive(Debug, Default, Deserialize)]
+struct AppMetadata {
+    role: Option<String>,
+    #[serde(default)]
+    roles: Vec<String>,
+}
+
+impl AppMetadata {
+    fn into_roles(self) -> Vec<String> {
+        let mut roles = self.roles;
+        if let Some(r) = self.role {
+            if !roles.contains(&r) {
+                roles.push(r);
+            }
+        }
+        roles
+    }
 }
 
 pub enum JwtConfig {
@@ -48,9 +78,110 @@ pub enum JwtConfig {
         jwks_url: String,
         issuer: String,
         audience: Option<String>,
+        cache: JwksCache,
     },
 }
 
+/// Used when the JWKS response has no usable Cache-Control max-age.
+const JWKS_DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+/// Minimum gap between JWKS fetches, so unknown kids or an outage cannot cause a fetch storm.
+const JWKS_MIN_REFETCH: Duration = Duration::from_secs(30);
+
+/// In-memory JWKS cache. Keys are reused until Cache-Control max-age expires or a token names
+/// an unknown kid; refetches are rate-limited, and the last good key set is served when a
+/// fetch fails.
+pub struct JwksCache {
+    client: reqwest::Client,
+    state: Mutex<JwksState>,
+}
+
+#[derive(Default)]
+struct JwksState {
+    keys: Option<JwkSet>,
+    expires_at: Option<Instant>,
+    last_fetch: Option<Instant>,
+}
+
+impl JwksCache {
+    fn new() -> Self {
+        // Bounded so a hung fetch cannot stall every authenticated request behind the lock.
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            state: Mutex::new(JwksState::default()),
+        }
+    }
+
+    /// Returns the key for `kid`, fetching the key set only when needed.
+    async fn key_for(&self, jwks_url: &str, kid: &str) -> Result<Jwk, String> {
+        // Held across the fetch so concurrent requests wait for one fetch instead of racing.
+        let mut state = self.state.lock().await;
+        let now = Instant::now();
+        let fresh = state.expires_at.is_some_and(|t| now < t);
+        let cached = state.keys.as_ref().and_then(|k| k.find(kid)).cloned();
+        if let (true, Some(jwk)) = (fresh, cached.as_ref()) {
+            return Ok(jwk.clone());
+        }
+        let may_fetch = state
+            .last_fetch
+            .is_none_or(|t| now.duration_since(t) >= JWKS_MIN_REFETCH);
+        if !may_fetch {
+            return cached.ok_or_else(|| format!("JWKS has no key for kid={:?} (refetch rate-limited)", kid));
+        }
+
+        state.last_fetch = Some(now);
+        match self.fetch(jwks_url).await {
+            Ok((keys, ttl)) => {
+                let jwk = keys.find(kid).cloned();
+                state.keys = Some(keys);
+                state.expires_at = Some(now + ttl);
+                jwk.ok_or_else(|| format!("JWKS has no key for kid={:?}", kid))
+            }
+            Err(e) => {
+                eprintln!("[auth] JWKS fetch failed (check SUPABASE_URL and network): {}", e);
+                cached.ok_or(e)
+            }
+        }
+    }
+
+    async fn fetch(&self, jwks_url: &str) -> Result<(JwkSet, Duration), String> {
+        let res = self
+            .client
+            .get(jwks_url)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("JWKS HTTP {}", res.status()));
+        }
+        let ttl = res
+            .headers()
+            .get(reqwest::header::CACHE_CONTROL)
+            .and_then(|v| v.to_str().ok())
+            .and_then(cache_control_max_age)
+            .unwrap_or(JWKS_DEFAULT_TTL);
+        let keys: JwkSet = res.json().await.map_err(|e| format!("JWKS parse error: {}", e))?;
+        Ok((keys, ttl))
+    }
+}
+
+/// max-age from a Cache-Control header; `no-store`/`no-cache` count as zero.
+fn cache_control_max_age(header: &str) -> Option<Duration> {
+    let mut max_age = None;
+    for directive in header.split(',').map(|d| d.trim().to_ascii_lowercase()) {
+        if directive == "no-store" || directive == "no-cache" {
+            return Some(Duration::ZERO);
+        }
+        if let Some(v) = directive.strip_prefix("max-age=") {
+            max_age = v.trim_matches('"').parse().ok().map(Duration::from_secs);
+        }
+    }
+    max_age
+}
+
 /// Derives Supabase project URL from a Supabase Postgres DATABASE_URL
 /// (e.g. postgresql://...@db.PROJECT_REF.supabase.co:5432/postgres -> https://PROJECT_REF.supabase.co).
 fn supabase_url_from_database_url(database_url: &str) -> Option<String> {
@@ -90,6 +221,7 @@ impl JwtConfig {
                 jwks_url: format!("{}/auth/v1/.well-known/jwks.json", url.trim_end_matches('/')),
                 issuer: format!("{}/auth/v1", url.trim_end_matches('/')),
                 audience,
+                cache: JwksCache::new(),
             });
         }
         Err("Set SUPABASE_JWT_SECRET (legacy), or SUPABASE_URL, or a Supabase DATABASE_URL for JWT signing keys".into())
@@ -113,6 +245,9 @@ impl<'r> FromRequest<'r> for User {
                 return Outcome::Error((rocket::http::Status::Unauthorized, ()));
             }
         };
+        if token.starts_with(API_TOKEN_PREFIX) {
+            return authenticate_api_token(req, token).await;
+        }
 
         let token_data = match config {
             JwtConfig::LegacySecret { secret, audience } => {
@@ -137,6 +272,7 @@ impl<'r> FromRequest<'r> for User {
                 jwks_url,
                 issuer,
                 audience,
+                cache,
             } => {
                 let header = match decode_header(token) {
                     Ok(h) => h,
@@ -152,27 +288,14 @@ impl<'r> FromRequest<'r> for User {
                         return Outcome::Error((rocket::http::Status::Unauthorized, ()));
                     }
                 };
-                let jwks: JwkSet = match reqwest::get(jwks_url.as_str()).await {
-                    Ok(r) => match r.json().await {
-                        Ok(j) => j,
-                        Err(e) => {
-                            eprintln!("[auth] 401: JWKS fetch parse error: {}", e);
-                            return Outcome::Error((rocket::http::Status::Unauthorized, ()));
-                        }
-                    },
+                let jwk = match cache.key_for(jwks_url, &kid).await {
+                    Ok(k) => k,
                     Err(e) => {
-                        eprintln!("[auth] 401: JWKS fetch failed (check SUPABASE_URL and network): {}", e);
+                        eprintln!("[auth] 401: {}", e);
                         return Outcome::Error((rocket::http::Status::Unauthorized, ()));
                     }
                 };
-                let jwk = match jwks.find(&kid) {
-                    Some(k) => k,
-                    None => {
-                        eprintln!("[auth] 401: JWKS has no key for kid={:?}", kid);
-                        return Outcome::Error((rocket::http::Status::Unauthorized, ()));
-                    }
-                };
-                let decoding_key = match DecodingKey::from_jwk(jwk) {
+                let decoding_key = match DecodingKey::from_jwk(&jwk) {
                     Ok(k) => k,
                     Err(e) => {
                         eprintln!("[auth] 401: DecodingKey from_jwk failed: {}", e);
@@ -206,10 +329,77 @@ impl<'r> FromRequest<'r> for User {
         Outcome::Success(User(UserContext {
             user_id,
             email: token_data.claims.email,
+            roles: token_data.claims.app_metadata.into_roles(),
+            token_scopes: None,
         }))
     }
 }
 
+/// Prefix of personal API tokens; any other Bearer value is treated as a Supabase JWT.
+pub const API_TOKEN_PREFIX: &str = "spd_";
+
+/// New random personal API token (shown to the user once).
+pub fn generate_api_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}{}", API_TOKEN_PREFIX, hex)
+}
+
+/// SHA-256 hex digest stored in api_token.token_hash.
+pub fn hash_api_token(token: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Resolves a personal API token. Safe methods need the `read` scope, everything else `write`.
+async fn authenticate_api_token(req: &Request<'_>, token: &str) -> Outcome<User, ()> {
+    use rocket::http::{Method, Status};
+    let store = match req.rocket().state::<crate::store::Store>() {
+        Some(s) => s,
+        None => return Outcome::Error((Status::InternalServerError, ())),
+    };
+    let found = match store.find_api_token_by_hash(&hash_api_token(token)).await {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("[auth] 500: API token lookup failed: {}", e);
+            return Outcome::Error((Status::InternalServerError, ()));
+        }
+    };
+    let Some(api_token) = found else {
+        eprintln!("[auth] 401: unknown API token");
+        return Outcome::Error((Status::Unauthorized, ()));
+    };
+    if api_token.revoked_at.is_some() {
+        eprintln!("[auth] 401: API token {} is revoked", api_token.id);
+        return Outcome::Error((Status::Unauthorized, ()));
+    }
+    if api_token.expires_at.is_some_and(|t| t <= chrono::Utc::now()) {
+        eprintln!("[auth] 401: API token {} has expired", api_token.id);
+        return Outcome::Error((Status::Unauthorized, ()));
+    }
+    let needed = match req.method() {
+        Method::Get | Method::Head | Method::Options => "read",
+        _ => "write",
+    };
+    if !api_token.scopes.iter().any(|s| s == needed) {
+        eprintln!("[auth] 403: API token {} lacks {} scope", api_token.id, needed);
+        return Outcome::Error((Status::Forbidden, ()));
+    }
+    let store = store.clone();
+    let token_id = api_token.id;
+    tokio::spawn(async move {
+        if let Err(e) = store.touch_api_token(token_id).await {
+            eprintln!("[auth] could not update last_used_at for token {}: {}", token_id, e);
+        }
+    });
+    Outcome::Success(User(UserContext {
+        user_id: api_token.user_id,
+        email: None,
+        roles: Vec::new(),
+        token_scopes: Some(api_token.scopes),
+    }))
+}
+
 /// Authenticated user that is also in approved_users. Use for newsletter routes.
 pub struct ApprovedUser(pub UserContext);
 
@@ -224,14 +414,48 @@ impl<'r> FromRequest<'r> for ApprovedUser {
             Outcome::Error(e) => return Outcome::Error(e),
             Outcome::Forward(f) => return Outcome::Forward(f),
         };
-        let supabase = match req.rocket().state::<crate::supabase::SupabaseClient>() {
+        let store = match req.rocket().state::<crate::store::Store>() {
             Some(s) => s,
             None => return Outcome::Error((rocket::http::Status::InternalServerError, ())),
         };
-        match supabase.is_user_approved(user.0.user_id).await {
+        match store.is_user_approved(user.0.user_id).await {
             Ok(true) => Outcome::Success(ApprovedUser(user.0)),
             Ok(false) => Outcome::Error((rocket::http::Status::Forbidden, ())),
             Err(_) => Outcome::Error((rocket::http::Status::InternalServerError, ())),
         }
     }
 }
+
+/// Authenticated user with the `admin` role, either in the JWT's app_metadata or in
+/// user_roles. Use for /api/admin routes. API tokens are for managing newsletters, so a token
+/// never acts as an admin, even one created by an admin.
+pub struct AdminUser(pub UserContext);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AdminUser {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let user = req.guard::<User>().await;
+        let user = match user {
+            Outcome::Success(u) => u,
+            Outcome::Error(e) => return Outcome::Error(e),
+            Outcome::Forward(f) => return Outcome::Forward(f),
+        };
+        if user.0.token_scopes.is_some() {
+            return Outcome::Error((rocket::http::Status::Forbidden, ()));
+        }
+        if user.0.roles.iter().any(|r| r == "admin") {
+            return Outcome::Success(AdminUser(user.0));
+        }
+        let store = match req.rocket().state::<crate::store::Store>() {
+            Some(s) => s,
+            None => return Outcome::Error((rocket::http::Status::InternalServerError, ())),
+        };
+        match store.user_has_role(user.0.user_id, "admin").await {
+            Ok(true) => Outcome::Success(AdminUser(user.0)),
+            Ok(false) => Outcome::Error((rocket::http::Status::Forbidden, ())),
+            Err(_) => Outcome::Error((rocket::http::Status::InternalServerError, ())),
+        }
+    }
+}
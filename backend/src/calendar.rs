@@ -0,0 +1,341 @@
+//! Pausing and blackout days: which scheduled issues are skipped, and reading blackout windows
+//! from an iCalendar (.ics) file such as an exported holiday calendar.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc, Weekday};
+
+use crate::models::{Blackout, BlackoutRepeat, NewsletterConfig};
+
+impl Blackout {
+    /// Whether `day` falls in this window or one of its repeats.
+    pub fn covers(&self, day: NaiveDate) -> bool {
+        if day < self.start || self.until.is_some_and(|u| day > u) {
+            return false;
+        }
+        let span = (self.end - self.start).num_days();
+        match self.repeat {
+            None => day <= self.end,
+            Some(BlackoutRepeat::Weekly) => (day - self.start).num_days() % 7 <= span,
+            // The window may run past new year, so also try the one that began the year before.
+            Some(BlackoutRepeat::Yearly) => [day.year() - 1, day.year()].into_iter().any(|year| {
+                let start = self
+                    .start
+                    .with_year(year)
+                    .or_else(|| NaiveDate::from_ymd_opt(year, 2, 28))
+                    .unwrap_or(self.start);
+                start <= day && day <= start + Duration::days(span)
+            }),
+        }
+    }
+
+    fn describe(&self) -> String {
+        if !self.reason.trim().is_empty() {
+            return self.reason.trim().to_string();
+        }
+        match self.repeat {
+            None if self.start == self.end => format!("blackout on {}", self.start),
+            None => format!("blackout {} to {}", self.start, self.end),
+            Some(BlackoutRepeat::Weekly) => format!("weekly blackout from {}", self.start),
+            Some(BlackoutRepeat::Yearly) => format!("yearly blackout from {}", self.start),
+        }
+    }
+}
+
+/// Why the issue due at `now` should be skipped, if it should: the newsletter is paused, or the
+/// UTC day is blacked out. A pause ends by itself once `paused_until` has passed.
+pub fn skip_reason(config: &NewsletterConfig, now: DateTime<Utc>) -> Option<String> {
+    if let Some(until) = config.paused_until.filter(|u| *u > now) {
+        return Some(format!("paused until {}", until.to_rfc3339()));
+    }
+    let today = now.date_naive();
+    config
+        .blackouts
+        .iter()
+        .find(|b| b.covers(today))
+        .map(|b| format!("blackout: {}", b.describe()))
+}
+
+/// Reads the VEVENTs of an iCalendar file as blackouts. Events are taken as whole days; a
+/// recurrence must be weekly (optionally BYDAY, giving one blackout per weekday) or yearly,
+/// with an optional UNTIL or COUNT. Anything else is reported rather than guessed at.
+pub fn parse_ical(text: &str) -> Result<Vec<Blackout>, String> {
+    let mut out = Vec::new();
+    let mut event: Option<Vec<(String, String, String)>> = None;
+    let mut nested = 0;
+    let mut index = 0;
+    for line in unfold(text) {
+        let Some((head, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = head.split_once(';').unwrap_or((head, ""));
+        let name = name.to_ascii_uppercase();
+        match (name.as_str(), value.trim()) {
+            ("BEGIN", "VEVENT") => event = Some(Vec::new()),
+            // Components inside an event (e.g. VALARM) have their own DTSTART and DURATION.
+            ("BEGIN", _) if event.is_some() => nested += 1,
+            ("END", v) if event.is_some() && v != "VEVENT" => nested -= 1,
+            ("END", "VEVENT") => {
+                index += 1;
+                let props = event.take().unwrap_or_default();
+                let blackouts = event_blackouts(&props).map_err(|e| format!("event {}: {}", index, e))?;
+                out.extend(blackouts);
+            }
+            _ => {
+                if let Some(props) = event.as_mut().filter(|_| nested == 0) {
+                    props.push((name, params.to_ascii_uppercase(), value.trim().to_string()));
+                }
+            }
+        }
+    }
+    if index == 0 {
+        return Err("no VEVENT found".into());
+    }
+    Ok(out)
+}
+
+/// Joins folded lines (RFC 5545 §3.1: a line starting with a space or tab continues the last).
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.lines() {
+        match (raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(raw.to_string()),
+        }
+    }
+    lines
+}
+
+fn event_blackouts(props: &[(String, String, String)]) -> Result<Vec<Blackout>, String> {
+    let get = |name: &str| props.iter().find(|(n, _, _)| n == name);
+    let (_, start_params, start_value) = get("DTSTART").ok_or("missing DTSTART")?;
+    let start = parse_day(start_value).ok_or_else(|| format!("bad DTSTART {}", start_value))?;
+    let all_day = start_params.contains("VALUE=DATE") || !start_value.contains('T');
+    let end = match (get("DTEND"), get("DURATION")) {
+        (Some((_, _, v)), _) => {
+            let end = parse_day(v).ok_or_else(|| format!("bad DTEND {}", v))?;
+            // DTEND is exclusive; for a timed event ending at midnight the last day is the one before.
+            let exclusive = all_day || v.contains("T000000");
+            if exclusive && end > start {
+                end - Duration::days(1)
+            } else {
+                end
+            }
+        }
+        (None, Some((_, _, v))) => parse_duration_days(v)
+            .and_then(|d| start.checked_add_signed(d))
+            .ok_or_else(|| format!("bad DURATION {}", v))?,
+        (None, None) => start,
+    };
+    if end < start {
+        return Err("DTEND is before DTSTART".into());
+    }
+    let reason = get("SUMMARY").map(|(_, _, v)| unescape(v)).unwrap_or_default();
+    let Some((_, _, rule)) = get("RRULE") else {
+        return Ok(vec![Blackout {
+            start,
+            end,
+            repeat: None,
+            until: None,
+            reason,
+        }]);
+    };
+
+    let mut freq = None;
+    let mut by_day = Vec::new();
+    let mut until = None;
+    let mut count = None;
+    for part in rule.split(';') {
+        let (key, value) = part.split_once('=').ok_or_else(|| format!("bad RRULE part {}", part))?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => freq = Some(value.to_ascii_uppercase()),
+            "BYDAY" => {
+                for d in value.split(',') {
+                    by_day.push(parse_weekday(d).ok_or_else(|| format!("unsupported BYDAY {}", d))?);
+                }
+            }
+            "UNTIL" => until = Some(parse_day(value).ok_or_else(|| format!("bad UNTIL {}", value))?),
+            "COUNT" => count = Some(value.parse::<u32>().map_err(|_| format!("bad COUNT {}", value))?),
+            "INTERVAL" if value == "1" => {}
+            "WKST" => {}
+            other => return Err(format!("unsupported RRULE part {}", other)),
+        }
+    }
+    let span = end - start;
+    let blackouts = match freq.as_deref() {
+        Some("YEARLY") if by_day.is_empty() => {
+            let until = match (until, count) {
+                (None, Some(c)) => Some(
+                    i32::try_from(c.saturating_sub(1))
+                        .ok()
+                        .and_then(|n| start.year().checked_add(n))
+                        .and_then(|year| start.with_year(year).or_else(|| NaiveDate::from_ymd_opt(year, 2, 28)))
+                        .and_then(|last| last.checked_add_signed(span))
+                        .ok_or_else(|| format!("COUNT {} is out of range", c))?,
+                ),
+                _ => until,
+            };
+            vec![Blackout {
+                start,
+                end,
+                repeat: Some(BlackoutRepeat::Yearly),
+                until,
+                reason,
+            }]
+        }
+        Some("WEEKLY") => {
+            if span.num_days() >= 7 {
+                return Err("a weekly event must be shorter than a week".into());
+            }
+            if by_day.is_empty() {
+                by_day.push(start.weekday());
+            }
+            if count.is_some() && by_day.len() > 1 {
+                return Err("COUNT with several BYDAY days is not supported; use UNTIL".into());
+            }
+            let out_of_range = || "RRULE is out of range".to_string();
+            let mut blackouts = Vec::new();
+            for weekday in by_day {
+                let offset = (7 + weekday.num_days_from_monday() as i64
+                    - start.weekday().num_days_from_monday() as i64)
+                    % 7;
+                let first = start
+                    .checked_add_signed(Duration::days(offset))
+                    .ok_or_else(out_of_range)?;
+                let first_end = first.checked_add_signed(span).ok_or_else(out_of_range)?;
+                let until = match (until, count) {
+                    (None, Some(c)) => Some(
+                        Duration::try_weeks(i64::from(c.saturating_sub(1)))
+                            .and_then(|weeks| first_end.checked_add_signed(weeks))
+                            .ok_or_else(|| format!("COUNT {} is out of range", c))?,
+                    ),
+                    _ => until,
+                };
+                blackouts.push(Blackout {
+                    start: first,
+                    end: first_end,
+                    repeat: Some(BlackoutRepeat::Weekly),
+                    until,
+                    reason: reason.clone(),
+                });
+            }
+            blackouts
+        }
+        Some(other) => return Err(format!("unsupported RRULE FREQ {}", other)),
+        None => return Err("RRULE without FREQ".into()),
+    };
+    Ok(blackouts)
+}
+
+/// `YYYYMMDD`, optionally followed by a `THHMMSS[Z]` time, which is ignored.
+fn parse_day(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+/// Whole days of a `P<n>D` or `P<n>W` duration; shorter durations count as the start day only.
+fn parse_duration_days(value: &str) -> Option<Duration> {
+    let rest = value.strip_prefix('P')?;
+    if let Some(weeks) = rest.strip_suffix('W') {
+        return Duration::try_weeks(weeks.parse().ok()?)?.checked_sub(&Duration::days(1));
+    }
+    let days = rest.split('T').next()?;
+    if days.is_empty() {
+        return Some(Duration::zero());
+    }
+    let n: i64 = days.strip_suffix('D')?.parse().ok()?;
+    Duration::try_days((n - 1).max(0))
+}
+
+fn parse_weekday(value: &str) -> Option<Weekday> {
+    match value.trim().to_ascii_uppercase().as_str() {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn ics(event: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n{}\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n", event.replace('\n', "\r\n"))
+    }
+
+    fn one(event: &str) -> Blackout {
+        let mut out = parse_ical(&ics(event)).unwrap();
+        assert_eq!(out.len(), 1);
+        out.remove(0)
+    }
+
+    #[test]
+    fn all_day_dtend_is_exclusive() {
+        let b = one("DTSTART;VALUE=DATE:20251224\nDTEND;VALUE=DATE:20251227\nSUMMARY:Holidays");
+        assert_eq!((b.start, b.end), (day("2025-12-24"), day("2025-12-26")));
+        assert_eq!(b.reason, "Holidays");
+    }
+
+    #[test]
+    fn timed_dtend_at_midnight_is_exclusive() {
+        let b = one("DTSTART:20250301T090000Z\nDTEND:20250303T000000Z");
+        assert_eq!((b.start, b.end), (day("2025-03-01"), day("2025-03-02")));
+        let b = one("DTSTART:20250301T090000Z\nDTEND:20250303T100000Z");
+        assert_eq!(b.end, day("2025-03-03"));
+    }
+
+    #[test]
+    fn byday_gives_one_weekly_blackout_per_day() {
+        // 2025-03-01 is a Saturday.
+        let out = parse_ical(&ics("DTSTART;VALUE=DATE:20250301\nRRULE:FREQ=WEEKLY;BYDAY=SA,SU,MO")).unwrap();
+        let starts: Vec<_> = out.iter().map(|b| b.start).collect();
+        assert_eq!(starts, [day("2025-03-01"), day("2025-03-02"), day("2025-03-03")]);
+        assert!(out.iter().all(|b| b.start == b.end && b.repeat == Some(BlackoutRepeat::Weekly)));
+        assert!(out[1].covers(day("2025-03-09")));
+        assert!(!out[1].covers(day("2025-03-10")));
+    }
+
+    #[test]
+    fn weekly_count_sets_until() {
+        let b = one("DTSTART;VALUE=DATE:20250301\nDTEND;VALUE=DATE:20250303\nRRULE:FREQ=WEEKLY;COUNT=3");
+        assert_eq!(b.until, Some(day("2025-03-16")));
+        assert!(b.covers(day("2025-03-16")));
+        assert!(!b.covers(day("2025-03-22")));
+    }
+
+    #[test]
+    fn yearly_count_sets_until() {
+        let b = one("DTSTART;VALUE=DATE:20251231\nDTEND;VALUE=DATE:20260102\nRRULE:FREQ=YEARLY;COUNT=2");
+        assert_eq!(b.until, Some(day("2027-01-01")));
+        assert!(b.covers(day("2027-01-01")));
+        assert!(!b.covers(day("2027-12-31")));
+    }
+
+    #[test]
+    fn out_of_range_numbers_are_errors() {
+        for event in [
+            "DTSTART;VALUE=DATE:20250301\nDURATION:P99999999999999W",
+            "DTSTART;VALUE=DATE:20250301\nDURATION:P999999999D",
+            "DTSTART;VALUE=DATE:20250301\nRRULE:FREQ=WEEKLY;COUNT=4000000000",
+            "DTSTART;VALUE=DATE:20250301\nRRULE:FREQ=YEARLY;COUNT=2147483648",
+            "DTSTART;VALUE=DATE:20250301\nRRULE:FREQ=YEARLY;COUNT=4000000000",
+        ] {
+            assert!(parse_ical(&ics(event)).is_err(), "{}", event);
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use lettre::message::header::ContentType;
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::transport::smtp::client::{Tls, TlsParametersBuilder};
@@ -19,14 +20,14 @@ pub async fn send_newsletter(
     to: &str,
     subject: &str,
     body: &str,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let email = Message::builder()
-        .from(config.from_address.parse().map_err(|e: lettre::address::AddressError| e.to_string())?)
-        .to(to.parse().map_err(|e: lettre::address::AddressError| e.to_string())?)
+        .from(config.from_address.parse().map_err(AppError::email)?)
+        .to(to.parse().map_err(AppError::email)?)
         .subject(subject)
         .header(ContentType::TEXT_PLAIN)
         .body(body.to_string())
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::email)?;
 
     let creds = Credentials::new(
         config.smtp_user.clone(),
@@ -37,16 +38,16 @@ pub async fn send_newsletter(
         let tls_params = TlsParametersBuilder::new(config.smtp_host.clone())
             .dangerous_accept_invalid_hostnames(true)
             .build_native()
-            .map_err(|e| e.to_string())?;
+            .map_err(AppError::email)?;
         match config.smtp_port {
             465 => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
-                .map_err(|e| e.to_string())?
+                .map_err(AppError::email)?
                 .port(config.smtp_port)
                 .credentials(creds)
                 .tls(Tls::Wrapper(tls_params))
                 .build(),
             _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
-                .map_err(|e| e.to_string())?
+                .map_err(AppError::email)?
                 .port(config.smtp_port)
                 .credentials(creds)
                 .tls(Tls::Required(tls_params))
@@ -55,18 +56,37 @@ pub async fn send_newsletter(
     } else {
         match config.smtp_port {
             465 => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
-                .map_err(|e| e.to_string())?
+                .map_err(AppError::email)?
                 .port(config.smtp_port)
                 .credentials(creds)
                 .build(),
             _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
-                .map_err(|e| e.to_string())?
+                .map_err(AppError::email)?
                 .port(config.smtp_port)
                 .credentials(creds)
                 .build(),
         }
     };
 
-    mailer.send(email).await.map_err(|e| e.to_string())?;
+    mailer.send(email).await.map_err(AppError::email)?;
     Ok(())
 }
+
+/// Renders generated markdown to HTML for previews.
+pub fn render_html(markdown: &str) -> String {
+    let parser = pulldown_cmark::Parser::new_ext(markdown, pulldown_cmark::Options::ENABLE_TABLES);
+    let mut html = String::new();
+    pulldown_cmark::html::push_html(&mut html, parser);
+    html
+}
+
+/// Tells a user their access request was approved.
+pub async fn send_access_approved(config: &EmailConfig, to: &str) -> Result<(), AppError> {
+    send_newsletter(
+        config,
+        to,
+        "Your Speedo access has been approved",
+        "Good news: your request for access to Speedo has been approved.\n\nSign in to set up your first newsletter.",
+    )
+    .await
+}
@@ -0,0 +1,218 @@
+//! Crate-wide error type. Routes return `AppError`, which renders as
+//! `{ "error": { "code", "message", "details" } }` with a matching status; the catchers
+//! registered in main.rs give guard and framework failures the same shape.
+
+use rocket::http::{Header, Status};
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::Json;
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug)]
+pub enum AppError {
+    /// The storage backend failed or returned an unexpected response.
+    Storage(String),
+    /// Missing or invalid credentials.
+    Unauthorized(String),
+    /// Authenticated but not allowed.
+    Forbidden(String),
+    /// The OpenClaw gateway failed or returned nothing usable.
+    Generation(String),
+    /// SMTP delivery failed.
+    Email(String),
+    /// Request content is well-formed but not acceptable; `details` carries specifics.
+    Validation {
+        message: String,
+        details: serde_json::Value,
+    },
+    NotFound(String),
+    BadRequest(String),
+    Conflict(String),
+    /// The resource changed since the client read it (`If-Match` failed); `current` is its
+    /// present state and `etag` that state's ETag.
+    Stale {
+        message: String,
+        current: serde_json::Value,
+        etag: String,
+    },
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    Internal(String),
+}
+
+impl AppError {
+    pub fn storage(e: impl fmt::Display) -> Self {
+        AppError::Storage(e.to_string())
+    }
+
+    pub fn generation(e: impl fmt::Display) -> Self {
+        AppError::Generation(e.to_string())
+    }
+
+    pub fn email(e: impl fmt::Display) -> Self {
+        AppError::Email(e.to_string())
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        AppError::Validation {
+            message: message.into(),
+            details: serde_json::Value::Null,
+        }
+    }
+
+    /// Parses a path id, reporting a malformed one as 400.
+    pub fn parse_id(id: &str) -> Result<uuid::Uuid, Self> {
+        uuid::Uuid::parse_str(id).map_err(|_| AppError::BadRequest(format!("Invalid id: {}", id)))
+    }
+
+    pub fn status(&self) -> Status {
+        match self {
+            AppError::Storage(_) | AppError::Internal(_) => Status::InternalServerError,
+            AppError::Unauthorized(_) => Status::Unauthorized,
+            AppError::Forbidden(_) => Status::Forbidden,
+            AppError::Generation(_) | AppError::Email(_) => Status::BadGateway,
+            AppError::Validation { .. } => Status::UnprocessableEntity,
+            AppError::NotFound(_) => Status::NotFound,
+            AppError::BadRequest(_) => Status::BadRequest,
+            AppError::Conflict(_) | AppError::Stale { .. } => Status::Conflict,
+            AppError::RateLimited { .. } => Status::TooManyRequests,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Storage(_) => "storage_error",
+            AppError::Unauthorized(_) => "unauthorized",
+            AppError::Forbidden(_) => "forbidden",
+            AppError::Generation(_) => "generation_failed",
+            AppError::Email(_) => "email_failed",
+            AppError::Validation { .. } => "validation_failed",
+            AppError::NotFound(_) => "not_found",
+            AppError::BadRequest(_) => "bad_request",
+            AppError::Conflict(_) => "conflict",
+            AppError::Stale { .. } => "stale",
+            AppError::RateLimited { .. } => "rate_limited",
+            AppError::Internal(_) => "internal_error",
+        }
+    }
+
+    /// Message safe to show the client. Storage and internal failures are logged in full
+    /// but reported generically.
+    fn public_message(&self) -> String {
+        match self {
+            AppError::Storage(_) => "Database request failed".into(),
+            AppError::Internal(_) => "Internal server error".into(),
+            other => other.message().to_string(),
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            AppError::Storage(m)
+            | AppError::Unauthorized(m)
+            | AppError::Forbidden(m)
+            | AppError::Generation(m)
+            | AppError::Email(m)
+            | AppError::NotFound(m)
+            | AppError::BadRequest(m)
+            | AppError::Conflict(m)
+            | AppError::Internal(m) => m,
+            AppError::Validation { message, .. }
+            | AppError::Stale { message, .. }
+            | AppError::RateLimited { message, .. } => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.code(), self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl<'r> Responder<'r, 'static> for AppError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let status = self.status();
+        if status.code >= 500 {
+            eprintln!("[error] {} {}: {}", req.method(), req.uri(), self);
+        }
+        let details = match &self {
+            AppError::Validation { details, .. } => details.clone(),
+            AppError::Stale { current, .. } => serde_json::json!({ "current": current }),
+            _ => serde_json::Value::Null,
+        };
+        let body = serde_json::json!({
+            "error": {
+                "code": self.code(),
+                "message": self.public_message(),
+                "details": details,
+            }
+        });
+        let mut res = Response::build_from(Json(body).respond_to(req)?);
+        res.status(status);
+        if let AppError::RateLimited {
+            retry_after: Some(after),
+            ..
+        } = &self
+        {
+            res.header(Header::new("Retry-After", after.as_secs().max(1).to_string()));
+        }
+        if let AppError::Stale { etag, .. } = &self {
+            res.header(Header::new("ETag", etag.clone()));
+        }
+        res.ok()
+    }
+}
+
+fn from_status(status: Status) -> AppError {
+    let reason = status.reason().unwrap_or("Error").to_string();
+    match status.code {
+        400 => AppError::BadRequest(reason),
+        401 => AppError::Unauthorized("Missing or invalid credentials".into()),
+        403 => AppError::Forbidden("Not allowed".into()),
+        404 => AppError::NotFound("Not found".into()),
+        409 => AppError::Conflict(reason),
+        422 => AppError::validation("Request body is invalid or could not be parsed"),
+        429 => AppError::RateLimited {
+            message: reason,
+            retry_after: None,
+        },
+        _ => AppError::Internal(reason),
+    }
+}
+
+#[rocket::catch(401)]
+pub fn unauthorized() -> AppError {
+    from_status(Status::Unauthorized)
+}
+
+#[rocket::catch(403)]
+pub fn forbidden() -> AppError {
+    AppError::Forbidden("Not allowed. Your account may not be approved yet.".into())
+}
+
+#[rocket::catch(404)]
+pub fn not_found() -> AppError {
+    from_status(Status::NotFound)
+}
+
+#[rocket::catch(422)]
+pub fn unprocessable() -> AppError {
+    from_status(Status::UnprocessableEntity)
+}
+
+#[rocket::catch(500)]
+pub fn internal() -> AppError {
+    AppError::Internal("Unhandled server error".into())
+}
+
+/// Everything else (e.g. 400 from malformed requests) gets the same JSON shape.
+#[rocket::catch(default)]
+pub fn default(status: Status, _req: &Request) -> (Status, AppError) {
+    (status, from_status(status))
+}
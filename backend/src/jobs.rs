@@ -0,0 +1,247 @@
+//! Background job queue shared by the scheduler and sample sends.
+//! Jobs are recorded in background_job so their status can be polled; a fixed pool of
+//! in-process workers executes them.
+
+use chrono::{NaiveDate, Utc};
+use reqwest::Client;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::email::{self, EmailConfig};
+use crate::error::AppError;
+use crate::models::NewsletterConfig;
+use crate::openclaw_client::{self, OpenClawConfig};
+use crate::prompts;
+use crate::scheduler;
+use crate::store::Store;
+
+/// Stages reported to a listener while a sample send runs.
+pub enum SampleProgress {
+    Queued { job_id: Uuid },
+    Generating,
+    Token(String),
+    Rendering { subject: String },
+    Sending,
+    Done,
+    Error(String),
+}
+
+pub type ProgressSender = mpsc::UnboundedSender<SampleProgress>;
+
+enum JobKind {
+    /// One-off send of a (possibly overlaid) config to its delivery email.
+    SendSample {
+        config: NewsletterConfig,
+        progress: Option<ProgressSender>,
+    },
+    /// A due scheduled issue; writes a run log like any scheduled send. `slot` is the UTC day
+    /// of the issue, claimed in the store so no instance sends it twice.
+    ScheduledRun { config: NewsletterConfig, slot: NaiveDate },
+}
+
+impl JobKind {
+    fn name(&self) -> &'static str {
+        match self {
+            JobKind::SendSample { .. } => "send_sample",
+            JobKind::ScheduledRun { .. } => "scheduled_run",
+        }
+    }
+
+    fn config(&self) -> &NewsletterConfig {
+        match self {
+            JobKind::SendSample { config, .. } | JobKind::ScheduledRun { config, .. } => config,
+        }
+    }
+
+    fn slot(&self) -> Option<NaiveDate> {
+        match self {
+            JobKind::SendSample { .. } => None,
+            JobKind::ScheduledRun { slot, .. } => Some(*slot),
+        }
+    }
+}
+
+struct Job {
+    id: Uuid,
+    kind: JobKind,
+}
+
+#[derive(Clone)]
+pub struct JobQueue {
+    tx: mpsc::Sender<Job>,
+    store: Store,
+    /// Recorded on every job this queue inserts, so a restart only fails its own jobs.
+    instance_id: String,
+}
+
+impl JobQueue {
+    /// Records and queues `kind`; None if its slot is already claimed by another run.
+    async fn enqueue(&self, kind: JobKind) -> Result<Option<Uuid>, AppError> {
+        let id = Uuid::new_v4();
+        let config = kind.config();
+        let inserted = self
+            .store
+            .insert_job(id, config.user_id, kind.name(), Some(config.id), kind.slot(), &self.instance_id)
+            .await?;
+        if !inserted {
+            return Ok(None);
+        }
+        if self.tx.send(Job { id, kind }).await.is_err() {
+            // Release the slot; the job will never run.
+            let _ = self.store.update_job_status(id, "failed", Some("Job queue is closed")).await;
+            return Err(AppError::Internal("Job queue is closed".into()));
+        }
+        Ok(Some(id))
+    }
+
+    /// Queues a sample send. `progress` receives stage updates when the caller streams them.
+    pub async fn enqueue_sample(
+        &self,
+        config: NewsletterConfig,
+        progress: Option<ProgressSender>,
+    ) -> Result<Uuid, AppError> {
+        let id = self
+            .enqueue(JobKind::SendSample {
+                config,
+                progress: progress.clone(),
+            })
+            .await?
+            .ok_or_else(|| AppError::Internal("Sample job was not queued".into()))?;
+        if let Some(p) = progress {
+            let _ = p.send(SampleProgress::Queued { job_id: id });
+        }
+        Ok(id)
+    }
+
+    /// Queues today's scheduled run unless a run of it is already queued, running or done.
+    pub async fn enqueue_scheduled_run(&self, config: NewsletterConfig) -> Result<Option<Uuid>, AppError> {
+        let slot = Utc::now().date_naive();
+        self.enqueue(JobKind::ScheduledRun { config, slot }).await
+    }
+
+    /// Queues a run of today's issue on request. Refused while a run is queued or running, and
+    /// once today's slot has been sent, so a retry can never deliver the same issue twice.
+    pub async fn enqueue_run_now(&self, config: NewsletterConfig) -> Result<Uuid, AppError> {
+        let now = Utc::now();
+        if let Some(at) = scheduler::slot_sent_at(&self.store, config.id, now).await? {
+            return Err(AppError::Conflict(format!(
+                "Today's issue was already sent at {}",
+                at.to_rfc3339()
+            )));
+        }
+        let slot = now.date_naive();
+        self.enqueue(JobKind::ScheduledRun { config, slot })
+            .await?
+            .ok_or_else(|| AppError::Conflict("A run of today's issue is already queued, running or sent".into()))
+    }
+}
+
+/// Spawns `workers` workers and returns the queue that feeds them.
+pub fn start_workers(
+    workers: usize,
+    store: Store,
+    instance_id: String,
+    openclaw: OpenClawConfig,
+    email_config: EmailConfig,
+) -> JobQueue {
+    let (tx, rx) = mpsc::channel::<Job>(256);
+    let rx = Arc::new(tokio::sync::Mutex::new(rx));
+    let queue = JobQueue {
+        tx,
+        store: store.clone(),
+        instance_id,
+    };
+    let client = Client::new();
+    for _ in 0..workers.max(1) {
+        let rx = rx.clone();
+        let store = store.clone();
+        let openclaw = openclaw.clone();
+        let email_config = email_config.clone();
+        let client = client.clone();
+        tokio::spawn(async move {
+            loop {
+                let job = rx.lock().await.recv().await;
+                let Some(job) = job else {
+                    break;
+                };
+                run_job(&store, &client, &openclaw, &email_config, job).await;
+            }
+        });
+    }
+    queue
+}
+
+async fn run_job(
+    store: &Store,
+    client: &Client,
+    openclaw: &OpenClawConfig,
+    email_config: &EmailConfig,
+    job: Job,
+) {
+    if let Err(e) = store.update_job_status(job.id, "running", None).await {
+        eprintln!("[jobs] {} mark running failed: {}", job.id, e);
+    }
+    let result = match &job.kind {
+        JobKind::SendSample { config, progress } => {
+            send_sample(store, client, openclaw, email_config, config, progress.as_ref()).await
+        }
+        JobKind::ScheduledRun { config, .. } => {
+            scheduler::run_one(store, client, openclaw, email_config, config).await
+        }
+    };
+    let (status, error) = match &result {
+        Ok(()) => ("succeeded", None),
+        Err(e) => {
+            eprintln!("[jobs] {} {} failed: {}", job.kind.name(), job.id, e);
+            ("failed", Some(e.to_string()))
+        }
+    };
+    if let Err(e) = store.update_job_status(job.id, status, error.as_deref()).await {
+        eprintln!("[jobs] {} mark {} failed: {}", job.id, status, e);
+    }
+}
+
+async fn send_sample(
+    store: &Store,
+    client: &Client,
+    openclaw: &OpenClawConfig,
+    email_config: &EmailConfig,
+    config: &NewsletterConfig,
+    progress: Option<&ProgressSender>,
+) -> Result<(), AppError> {
+    let report = |p: SampleProgress| {
+        if let Some(tx) = progress {
+            let _ = tx.send(p);
+        }
+    };
+    let result = async {
+        report(SampleProgress::Generating);
+        let prompt = prompts::build_for(store, config).await?;
+        let body = openclaw_client::generate_newsletter_streaming(client, openclaw, &prompt, |delta| {
+            report(SampleProgress::Token(delta.to_string()))
+        })
+        .await?;
+        let body = body.trim();
+        if body.is_empty() {
+            return Err(AppError::Generation(
+                "OpenClaw did not return any content. Check the agent and gateway.".into(),
+            ));
+        }
+
+        let subject = format!("{} – Sample – {}", config.title, Utc::now().format("%Y-%m-%d %H:%M"));
+        report(SampleProgress::Rendering {
+            subject: subject.clone(),
+        });
+        report(SampleProgress::Sending);
+        email::send_newsletter(email_config, &config.delivery_email, &subject, body).await?;
+        eprintln!("[jobs] sample sent to {}", config.delivery_email);
+        Ok(())
+    }
+    .await;
+    match &result {
+        Ok(()) => report(SampleProgress::Done),
+        Err(e) => report(SampleProgress::Error(e.to_string())),
+    }
+    result
+}
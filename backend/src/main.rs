@@ -1,29 +1,94 @@
 mod auth;
+mod calendar;
 mod email;
+mod error;
+mod jobs;
 mod models;
 mod openclaw_client;
+mod prompts;
+mod rate_limit;
 mod routes;
 mod scheduler;
-mod supabase;
+mod store;
+mod validation;
 
 use rocket_cors::{AllowedOrigins, CorsOptions};
 
 #[rocket::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenvy::dotenv().ok();
-    let supabase_url = std::env::var("SUPABASE_URL").expect("SUPABASE_URL must be set (e.g. https://PROJECT_REF.supabase.co)");
-    let supabase_key = std::env::var("SUPABASE_SERVICE_ROLE_KEY")
-        .expect("SUPABASE_SERVICE_ROLE_KEY must be set for backend REST API");
+    let command: Vec<String> = std::env::args().skip(1).collect();
+    let supabase_url = std::env::var("SUPABASE_URL").ok();
+    let database_url = std::env::var("DATABASE_URL").ok();
+
+    let backend = match std::env::var("STORAGE_BACKEND").as_deref().unwrap_or("supabase") {
+        "supabase" => store::StorageBackend::Supabase {
+            url: supabase_url
+                .clone()
+                .expect("SUPABASE_URL must be set (e.g. https://PROJECT_REF.supabase.co)"),
+            service_role_key: std::env::var("SUPABASE_SERVICE_ROLE_KEY")
+                .expect("SUPABASE_SERVICE_ROLE_KEY must be set for backend REST API"),
+        },
+        "postgres" => store::StorageBackend::Postgres {
+            url: database_url
+                .clone()
+                .expect("DATABASE_URL must be set for STORAGE_BACKEND=postgres"),
+            max_connections: std::env::var("DATABASE_MAX_CONNECTIONS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+        },
+        "sqlite" => store::StorageBackend::Sqlite {
+            url: std::env::var("SQLITE_URL").unwrap_or_else(|_| "sqlite://speedo.db".into()),
+        },
+        "memory" => store::StorageBackend::Memory,
+        other => panic!("STORAGE_BACKEND must be supabase, postgres, sqlite or memory (got {})", other),
+    };
+    eprintln!("storage backend: {}", backend.name());
+    let store = store::open(backend).await?;
+
+    // `migrate` applies pending migrations; `migrate status` lists them and fails if any remain.
+    match command.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        [] => {}
+        ["migrate"] => {
+            let applied = store.apply_migrations().await.map_err(|e| format!("migrate: {}", e))?;
+            for m in &applied {
+                println!("applied {} {}", m.version, m.description);
+            }
+            if applied.is_empty() {
+                println!("schema is up to date");
+            }
+            return Ok(());
+        }
+        ["migrate", "status"] => {
+            let pending = store.pending_migrations().await.map_err(|e| format!("migrate status: {}", e))?;
+            for m in &pending {
+                println!("pending {} {}", m.version, m.description);
+            }
+            if !pending.is_empty() {
+                std::process::exit(1);
+            }
+            println!("schema is up to date");
+            return Ok(());
+        }
+        _ => return Err(format!("unknown command: {}; expected `migrate` or `migrate status`", command.join(" ")).into()),
+    }
+    let migrate_on_startup = !matches!(
+        std::env::var("MIGRATE_ON_STARTUP").as_deref(),
+        Ok("0") | Ok("false") | Ok("no")
+    );
+    store::ensure_schema(&store, migrate_on_startup)
+        .await
+        .map_err(|e| format!("schema check: {}", e))?;
+
     let jwt_secret = std::env::var("SUPABASE_JWT_SECRET").ok();
     let jwt_audience = std::env::var("SUPABASE_JWT_AUDIENCE").ok();
     let jwt_config = auth::JwtConfig::from_env(
         jwt_secret.as_deref(),
         jwt_audience,
-        None,
-        Some(&supabase_url),
-    ).expect("set SUPABASE_JWT_SECRET (legacy) or SUPABASE_URL for JWT signing keys");
-
-    let supabase = supabase::SupabaseClient::new(supabase_url.clone(), supabase_key);
+        database_url.as_deref(),
+        supabase_url.as_deref(),
+    ).expect("set SUPABASE_JWT_SECRET (legacy), SUPABASE_URL or a Supabase DATABASE_URL for JWT signing keys");
 
     let openclaw_url = std::env::var("OPENCLAW_GATEWAY_URL").unwrap_or_else(|_| String::new());
     let openclaw_token = std::env::var("OPENCLAW_GATEWAY_TOKEN").unwrap_or_else(|_| String::new());
@@ -52,7 +117,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         tls_skip_verify: smtp_tls_skip,
     };
 
-    scheduler::run_scheduler(supabase.clone(), openclaw_config.clone(), email_config.clone());
+    // Jobs are tagged with the instance that queued them; keep this stable across restarts.
+    let instance_id = std::env::var("INSTANCE_ID")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "default".into());
+    if let Err(e) = store.fail_interrupted_jobs(&instance_id).await {
+        eprintln!("could not mark interrupted jobs as failed: {}", e);
+    }
+    let job_workers: usize = std::env::var("JOB_WORKERS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(2);
+    let job_queue = jobs::start_workers(
+        job_workers,
+        store.clone(),
+        instance_id,
+        openclaw_config.clone(),
+        email_config.clone(),
+    );
+    let quotas = rate_limit::Quotas::from_env();
+    scheduler::run_scheduler(store.clone(), job_queue.clone(), quotas.clone());
 
     let cors_origins = std::env::var("CORS_ORIGINS")
         .unwrap_or_else(|_| "*".into());
@@ -65,6 +149,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
     let cors = CorsOptions {
         allowed_origins: origins,
+        expose_headers: ["ETag", "X-Total-Count", "X-Next-Cursor"]
+            .iter()
+            .map(|h| h.to_string())
+            .collect(),
         ..CorsOptions::default()
     }
     .to_cors()
@@ -73,11 +161,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let http_client = reqwest::Client::new();
     let _ = rocket::build()
         .attach(cors)
-        .manage(supabase)
+        .manage(store)
         .manage(jwt_config)
         .manage(openclaw_config)
         .manage(email_config)
         .manage(http_client)
+        .manage(quotas)
+        .manage(job_queue)
         .mount(
             "/api",
             rocket::routes![
@@ -87,9 +177,61 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 routes::newsletters::create,
                 routes::newsletters::get,
                 routes::newsletters::update,
+                routes::newsletters::patch,
+                routes::newsletters::duplicate,
+                routes::newsletters::import_blackouts,
+                routes::import_export::export_all,
+                routes::import_export::export_one,
+                routes::import_export::import,
                 routes::newsletters::delete,
                 routes::newsletters::send_sample_options,
                 routes::newsletters::send_sample,
+                routes::newsletters::send_sample_stream_options,
+                routes::newsletters::send_sample_stream,
+                routes::newsletters::run_now,
+                routes::newsletters::schedule,
+                routes::newsletters::preview_options,
+                routes::newsletters::preview,
+                routes::sections::list,
+                routes::sections::create,
+                routes::sections::update,
+                routes::sections::delete,
+                routes::prompt_templates::list,
+                routes::presets::list,
+                routes::presets::create,
+                routes::presets::delete,
+                routes::presets::instantiate,
+                routes::presets::admin_create,
+                routes::presets::admin_delete,
+                routes::jobs::get,
+                routes::usage::get,
+                routes::api_tokens::create,
+                routes::api_tokens::list,
+                routes::api_tokens::revoke,
+                routes::access_requests::create,
+                routes::access_requests::get,
+                routes::admin::list_access_requests,
+                routes::admin::approve_access_request,
+                routes::admin::reject_access_request,
+                routes::admin::list_users,
+                routes::admin::approve_user,
+                routes::admin::revoke_user,
+                routes::admin::list_newsletters,
+                routes::admin::list_run_logs,
+                routes::admin::pause_newsletter,
+                routes::admin::resume_newsletter,
+                routes::admin::force_run,
+            ],
+        )
+        .register(
+            "/",
+            rocket::catchers![
+                error::unauthorized,
+                error::forbidden,
+                error::not_found,
+                error::unprocessable,
+                error::internal,
+                error::default,
             ],
         )
         .launch()
@@ -1,67 +1,520 @@
-use chrono::{DateTime, NaiveTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewsletterConfig {
     pub id: Uuid,
     pub user_id: Uuid,
     pub title: String,
     pub topics: Vec<String>,
+    /// Free-form labels for organising the list; not used in generation.
+    pub tags: Vec<String>,
     pub tone: String,
     pub length: String,
     pub send_time_utc: NaiveTime,
     pub timezone: String,
     pub delivery_email: String,
     pub is_active: bool,
+    /// Scheduled issues are skipped until this instant, then resume on their own.
+    pub paused_until: Option<DateTime<Utc>>,
+    /// Days (UTC) on which scheduled issues are skipped.
+    pub blackouts: Vec<Blackout>,
     pub features: serde_json::Value,
+    /// Pinned prompt template version; None follows the latest.
+    pub prompt_template_version: Option<i32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CreateNewsletterConfig {
     pub title: Option<String>,
     pub topics: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
     pub tone: Option<String>,
     pub length: Option<String>,
     pub send_time_utc: Option<String>,
     pub timezone: Option<String>,
     pub delivery_email: Option<String>,
     pub is_active: Option<bool>,
+    /// RFC 3339 instant, or a `YYYY-MM-DD` date meaning the start of that UTC day.
+    pub paused_until: Option<String>,
+    pub blackouts: Option<Vec<Blackout>>,
     pub features: Option<serde_json::Value>,
+    pub prompt_template_version: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateNewsletterConfig {
     pub title: Option<String>,
     pub topics: Option<Vec<String>>,
+    pub tags: Option<Vec<String>>,
     pub tone: Option<String>,
     pub length: Option<String>,
     pub send_time_utc: Option<String>,
     pub timezone: Option<String>,
     pub delivery_email: Option<String>,
     pub is_active: Option<bool>,
+    /// RFC 3339 instant, or a `YYYY-MM-DD` date meaning the start of that UTC day.
+    pub paused_until: Option<String>,
+    pub blackouts: Option<Vec<Blackout>>,
     pub features: Option<serde_json::Value>,
+    pub prompt_template_version: Option<i32>,
+}
+
+impl From<&CreateNewsletterConfig> for UpdateNewsletterConfig {
+    fn from(c: &CreateNewsletterConfig) -> Self {
+        UpdateNewsletterConfig {
+            title: c.title.clone(),
+            topics: c.topics.clone(),
+            tags: c.tags.clone(),
+            tone: c.tone.clone(),
+            length: c.length.clone(),
+            send_time_utc: c.send_time_utc.clone(),
+            timezone: c.timezone.clone(),
+            delivery_email: c.delivery_email.clone(),
+            is_active: c.is_active,
+            paused_until: c.paused_until.clone(),
+            blackouts: c.blackouts.clone(),
+            features: c.features.clone(),
+            prompt_template_version: c.prompt_template_version,
+        }
+    }
+}
+
+/// Export/import file: `{"version": 1, "newsletters": [...]}`, each entry shaped like a create
+/// body. Ids and timestamps are left out so a bundle can be applied to any account.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NewsletterBundle {
+    pub version: u32,
+    pub newsletters: Vec<CreateNewsletterConfig>,
+}
+
+impl NewsletterBundle {
+    pub const VERSION: u32 = 1;
+
+    pub fn new(newsletters: Vec<CreateNewsletterConfig>) -> Self {
+        NewsletterBundle {
+            version: Self::VERSION,
+            newsletters,
+        }
+    }
+}
+
+/// A PATCH field: absent (leave unchanged), explicit `null` (clear), or a new value.
+/// Fields of this type need `#[serde(default)]` so that absent deserializes as `Missing`.
+#[derive(Debug, Default)]
+pub enum Patch<T> {
+    #[default]
+    Missing,
+    Null,
+    Value(T),
+}
+
+impl<T> Patch<T> {
+    pub fn as_value(&self) -> Option<&T> {
+        match self {
+            Patch::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Patch<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        Ok(Option::<T>::deserialize(d)?.map_or(Patch::Null, Patch::Value))
+    }
+}
+
+/// PATCH body. `null` resets a field to its default (and unpins prompt_template_version);
+/// `features` is a JSON Merge Patch (RFC 7396) applied to the current map.
+#[derive(Debug, Deserialize)]
+pub struct PatchNewsletterConfig {
+    #[serde(default)]
+    pub title: Patch<String>,
+    #[serde(default)]
+    pub topics: Patch<Vec<String>>,
+    #[serde(default)]
+    pub tags: Patch<Vec<String>>,
+    #[serde(default)]
+    pub tone: Patch<String>,
+    #[serde(default)]
+    pub length: Patch<String>,
+    #[serde(default)]
+    pub send_time_utc: Patch<String>,
+    #[serde(default)]
+    pub timezone: Patch<String>,
+    #[serde(default)]
+    pub delivery_email: Patch<String>,
+    #[serde(default)]
+    pub is_active: Patch<bool>,
+    #[serde(default)]
+    pub paused_until: Patch<String>,
+    #[serde(default)]
+    pub blackouts: Patch<Vec<Blackout>>,
+    #[serde(default)]
+    pub features: Patch<serde_json::Value>,
+    #[serde(default)]
+    pub prompt_template_version: Patch<i32>,
 }
 
 impl NewsletterConfig {
-    pub fn into_api_response(self) -> serde_json::Value {
+    /// Strong ETag for this revision: the quoted `updated_at`, exactly as in the API response.
+    pub fn etag(&self) -> String {
+        format!("\"{}\"", self.updated_at.to_rfc3339())
+    }
+
+    /// A create body that reproduces this config under a new id.
+    pub fn to_create_body(&self) -> CreateNewsletterConfig {
+        CreateNewsletterConfig {
+            title: Some(self.title.clone()),
+            topics: Some(self.topics.clone()),
+            tags: Some(self.tags.clone()),
+            tone: Some(self.tone.clone()),
+            length: Some(self.length.clone()),
+            send_time_utc: Some(self.send_time_utc.format("%H:%M:%S").to_string()),
+            timezone: Some(self.timezone.clone()),
+            delivery_email: Some(self.delivery_email.clone()),
+            is_active: Some(self.is_active),
+            paused_until: self.paused_until.map(|t| t.to_rfc3339()),
+            blackouts: Some(self.blackouts.clone()),
+            features: Some(self.features.clone()),
+            prompt_template_version: self.prompt_template_version,
+        }
+    }
+
+    /// `last_run` is the newsletter's latest run log, if any; `next_run_at` is computed by the
+    /// caller from the schedule.
+    pub fn into_api_response(self, last_run: Option<&RunLog>, next_run_at: Option<DateTime<Utc>>) -> serde_json::Value {
         let send_time_utc = self.send_time_utc.format("%H:%M").to_string();
         serde_json::json!({
             "id": self.id,
             "user_id": self.user_id,
             "title": self.title,
             "topics": self.topics,
+            "tags": self.tags,
             "tone": self.tone,
             "length": self.length,
             "send_time_utc": send_time_utc,
             "timezone": self.timezone,
             "delivery_email": self.delivery_email,
             "is_active": self.is_active,
+            "paused_until": self.paused_until.map(|t| t.to_rfc3339()),
+            "blackouts": self.blackouts,
+            "features": self.features,
+            "prompt_template_version": self.prompt_template_version,
+            "next_run_at": next_run_at.map(|t| t.to_rfc3339()),
+            "last_run": last_run.map(|r| serde_json::json!({
+                "status": r.status,
+                "run_at": r.run_at.to_rfc3339(),
+                "error_message": r.error_message,
+            })),
+            "created_at": self.created_at.to_rfc3339(),
+            "updated_at": self.updated_at.to_rfc3339(),
+        })
+    }
+}
+
+/// Days on which scheduled issues are skipped: `start` to `end` inclusive, or with `repeat`
+/// the same window every week or year from `start`, up to `until` if given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blackout {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<BlackoutRepeat>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlackoutRepeat {
+    Weekly,
+    Yearly,
+}
+
+/// Entry in the section catalog. Built-in sections have no `user_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsletterSection {
+    pub id: Uuid,
+    pub key: String,
+    pub user_id: Option<Uuid>,
+    pub name: String,
+    pub description: String,
+    pub default_instructions: String,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNewsletterSection {
+    pub key: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub default_instructions: Option<String>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateNewsletterSection {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub default_instructions: Option<String>,
+    pub sort_order: Option<i32>,
+}
+
+impl NewsletterSection {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "key": self.key,
+            "built_in": self.user_id.is_none(),
+            "name": self.name,
+            "description": self.description,
+            "default_instructions": self.default_instructions,
+            "sort_order": self.sort_order,
+            "created_at": self.created_at.to_rfc3339(),
+            "updated_at": self.updated_at.to_rfc3339(),
+        })
+    }
+}
+
+/// Gallery entry a newsletter can be started from. Curated presets are published by admins;
+/// `user_id` is whoever published it (None once that account is gone).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewsletterPreset {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub curated: bool,
+    pub name: String,
+    pub description: String,
+    pub topics: Vec<String>,
+    pub tone: String,
+    pub length: String,
+    /// Same shape as `NewsletterConfig::features`; `custom_request` carries the prompt instructions.
+    pub features: serde_json::Value,
+    pub prompt_template_version: Option<i32>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateNewsletterPreset {
+    pub name: String,
+    pub description: Option<String>,
+    pub topics: Option<Vec<String>>,
+    pub tone: Option<String>,
+    pub length: Option<String>,
+    pub features: Option<serde_json::Value>,
+    pub prompt_template_version: Option<i32>,
+}
+
+impl NewsletterPreset {
+    /// The create body a newsletter instantiated from this preset starts with; the name becomes
+    /// the title.
+    pub fn to_create_body(&self) -> CreateNewsletterConfig {
+        CreateNewsletterConfig {
+            title: Some(self.name.clone()),
+            topics: Some(self.topics.clone()),
+            tone: Some(self.tone.clone()),
+            length: Some(self.length.clone()),
+            features: Some(self.features.clone()),
+            prompt_template_version: self.prompt_template_version,
+            ..CreateNewsletterConfig::default()
+        }
+    }
+
+    /// `mine` tells the viewer whether they published it; the publisher's id is not exposed.
+    pub fn into_api_response(self, viewer: Uuid) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "curated": self.curated,
+            "mine": self.user_id == Some(viewer),
+            "name": self.name,
+            "description": self.description,
+            "topics": self.topics,
+            "tone": self.tone,
+            "length": self.length,
             "features": self.features,
+            "prompt_template_version": self.prompt_template_version,
             "created_at": self.created_at.to_rfc3339(),
             "updated_at": self.updated_at.to_rfc3339(),
         })
     }
 }
+
+/// One version of the prompt wording. Snippets and guidance are JSON objects keyed by
+/// section key, tone and length respectively.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub version: i32,
+    pub system_instructions: String,
+    pub header_template: String,
+    pub section_template: String,
+    pub section_instructions_template: String,
+    pub section_snippets: serde_json::Value,
+    pub tone_guidance: serde_json::Value,
+    pub length_guidance: serde_json::Value,
+    pub notes: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PromptTemplate {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "version": self.version,
+            "system_instructions": self.system_instructions,
+            "header_template": self.header_template,
+            "section_template": self.section_template,
+            "section_instructions_template": self.section_instructions_template,
+            "section_snippets": self.section_snippets,
+            "tone_guidance": self.tone_guidance,
+            "length_guidance": self.length_guidance,
+            "notes": self.notes,
+            "created_at": self.created_at.to_rfc3339(),
+        })
+    }
+}
+
+/// Row in background_job. `kind` is `send_sample` or `scheduled_run`; `status` moves
+/// queued -> running -> succeeded | failed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackgroundJob {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub newsletter_config_id: Option<Uuid>,
+    pub status: String,
+    pub error_message: Option<String>,
+    /// UTC day whose issue a scheduled run sends; at most one unfailed run per newsletter and day.
+    pub slot: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl BackgroundJob {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "kind": self.kind,
+            "newsletter_config_id": self.newsletter_config_id,
+            "status": self.status,
+            "error_message": self.error_message,
+            "created_at": self.created_at.to_rfc3339(),
+            "started_at": self.started_at.map(|t| t.to_rfc3339()),
+            "finished_at": self.finished_at.map(|t| t.to_rfc3339()),
+        })
+    }
+}
+
+/// Row in access_request. `status` is `pending`, `approved` or `rejected`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessRequest {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub email: String,
+    pub message: String,
+    pub status: String,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAccessRequest {
+    pub message: Option<String>,
+}
+
+impl AccessRequest {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "user_id": self.user_id,
+            "email": self.email,
+            "message": self.message,
+            "status": self.status,
+            "reviewed_by": self.reviewed_by,
+            "reviewed_at": self.reviewed_at.map(|t| t.to_rfc3339()),
+            "created_at": self.created_at.to_rfc3339(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovedUserRow {
+    pub user_id: Uuid,
+    pub approved_at: DateTime<Utc>,
+    pub approved_by: Option<Uuid>,
+}
+
+/// Row in newsletter_run_log. `status` is success, failure or skipped; `error_message` holds the
+/// failure, or the reason an issue was skipped (paused, blackout).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunLog {
+    pub id: Uuid,
+    pub newsletter_config_id: Uuid,
+    pub run_at: DateTime<Utc>,
+    pub status: String,
+    pub error_message: Option<String>,
+    pub openclaw_response_id: Option<String>,
+    #[serde(default)]
+    pub prompt_template_version: Option<i32>,
+}
+
+impl RunLog {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "newsletter_config_id": self.newsletter_config_id,
+            "run_at": self.run_at.to_rfc3339(),
+            "status": self.status,
+            "error_message": self.error_message,
+            "openclaw_response_id": self.openclaw_response_id,
+            "prompt_template_version": self.prompt_template_version,
+        })
+    }
+}
+
+/// Personal API token as stored in api_token, without the hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiToken {
+    pub name: String,
+    /// `read` and/or `write`; defaults to `["read"]`.
+    pub scopes: Option<Vec<String>>,
+    pub expires_in_days: Option<u32>,
+}
+
+impl ApiToken {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "id": self.id,
+            "name": self.name,
+            "token_prefix": self.token_prefix,
+            "scopes": self.scopes,
+            "expires_at": self.expires_at.map(|t| t.to_rfc3339()),
+            "last_used_at": self.last_used_at.map(|t| t.to_rfc3339()),
+            "revoked_at": self.revoked_at.map(|t| t.to_rfc3339()),
+            "created_at": self.created_at.to_rfc3339(),
+        })
+    }
+}
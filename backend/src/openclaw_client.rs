@@ -1,4 +1,5 @@
-use crate::models::NewsletterConfig;
+use crate::error::AppError;
+use crate::prompts::Prompt;
 use reqwest::Client;
 use serde_json::Value;
 
@@ -9,25 +10,28 @@ pub struct OpenClawConfig {
     pub agent_id: String,
 }
 
-pub async fn generate_newsletter(
+async fn send_request(
     client: &Client,
     config: &OpenClawConfig,
-    newsletter: &NewsletterConfig,
-) -> Result<String, String> {
+    prompt: &Prompt,
+    stream: bool,
+) -> Result<reqwest::Response, AppError> {
     if config.gateway_url.is_empty() {
-        return Err("OpenClaw not configured (OPENCLAW_GATEWAY_URL empty). Set it in .env when ready.".into());
+        return Err(AppError::Generation(
+            "OpenClaw not configured (OPENCLAW_GATEWAY_URL empty). Set it in .env when ready.".into(),
+        ));
     }
-    let prompt = build_prompt(newsletter);
     let body = serde_json::json!({
         "model": format!("openclaw:{}", config.agent_id),
         "input": [
             {
                 "type": "message",
                 "role": "user",
-                "content": prompt
+                "content": prompt.input
             }
         ],
-        "instructions": "You are a newsletter writer. Produce a single newsletter document. Include only the sections the user requested. Follow their per-section instructions. Output plain text or markdown suitable for email."
+        "instructions": prompt.instructions,
+        "stream": stream
     });
 
     let res = client
@@ -37,15 +41,109 @@ pub async fn generate_newsletter(
         .json(&body)
         .send()
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(AppError::generation)?;
 
     if !res.status().is_success() {
         let status = res.status();
         let text = res.text().await.unwrap_or_default();
-        return Err(format!("OpenClaw HTTP {}: {}", status, text));
+        return Err(AppError::Generation(format!("OpenClaw HTTP {}: {}", status, text)));
     }
+    Ok(res)
+}
 
-    let json: Value = res.json().await.map_err(|e| e.to_string())?;
+pub async fn generate_newsletter(
+    client: &Client,
+    config: &OpenClawConfig,
+    prompt: &Prompt,
+) -> Result<String, AppError> {
+    let res = send_request(client, config, prompt, false).await?;
+    let json: Value = res.json().await.map_err(AppError::generation)?;
+    Ok(output_text(&json))
+}
+
+/// Like `generate_newsletter`, but asks the gateway to stream and calls `on_delta` with each
+/// text fragment as it arrives. Gateways that ignore `stream` and answer with a single JSON
+/// body are handled too; the whole text is then passed to `on_delta` once.
+pub async fn generate_newsletter_streaming(
+    client: &Client,
+    config: &OpenClawConfig,
+    prompt: &Prompt,
+    mut on_delta: impl FnMut(&str),
+) -> Result<String, AppError> {
+    let mut res = send_request(client, config, prompt, true).await?;
+    let is_sse = res
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"));
+    if !is_sse {
+        let json: Value = res.json().await.map_err(AppError::generation)?;
+        let text = output_text(&json);
+        if !text.is_empty() {
+            on_delta(&text);
+        }
+        return Ok(text);
+    }
+
+    // Buffer raw bytes so multi-byte characters split across chunks decode correctly.
+    let mut buf: Vec<u8> = Vec::new();
+    let mut text = String::new();
+    while let Some(chunk) = res.chunk().await.map_err(AppError::generation)? {
+        buf.extend(chunk.iter().filter(|b| **b != b'\r'));
+        while let Some(end) = buf.windows(2).position(|w| w == b"\n\n") {
+            let raw: Vec<u8> = buf.drain(..end + 2).collect();
+            let block = String::from_utf8_lossy(&raw);
+            let mut event = "";
+            let mut data = String::new();
+            for line in block.lines() {
+                if let Some(e) = line.strip_prefix("event:") {
+                    event = e.trim();
+                } else if let Some(d) = line.strip_prefix("data:") {
+                    data.push_str(d.trim_start());
+                }
+            }
+            if data.is_empty() || data == "[DONE]" {
+                continue;
+            }
+            let json: Value = match serde_json::from_str(&data) {
+                Ok(j) => j,
+                Err(_) => continue,
+            };
+            let kind = json.get("type").and_then(|t| t.as_str()).unwrap_or(event);
+            match kind {
+                "response.output_text.delta" => {
+                    if let Some(delta) = json.get("delta").and_then(|d| d.as_str()) {
+                        text.push_str(delta);
+                        on_delta(delta);
+                    }
+                }
+                "response.completed" => {
+                    // Prefer the final document if the gateway sends one; deltas may be partial.
+                    if let Some(response) = json.get("response") {
+                        let full = output_text(response);
+                        if !full.is_empty() {
+                            text = full;
+                        }
+                    }
+                }
+                "response.failed" | "error" => {
+                    let message = json
+                        .pointer("/response/error/message")
+                        .or_else(|| json.pointer("/error/message"))
+                        .or_else(|| json.get("message"))
+                        .and_then(|m| m.as_str())
+                        .unwrap_or("generation failed");
+                    return Err(AppError::Generation(format!("OpenClaw stream error: {}", message)));
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(text)
+}
+
+/// Joins the `output_text` parts of the first message in a Responses API payload.
+fn output_text(json: &Value) -> String {
     let output = json
         .get("output")
         .and_then(|o| o.as_array())
@@ -54,7 +152,7 @@ pub async fn generate_newsletter(
                 .find(|i| i.get("type").and_then(|t| t.as_str()) == Some("message"))
         })
         .and_then(|m| m.get("content"));
-    let text = match output {
+    match output {
         Some(Value::Array(content_parts)) => content_parts
             .iter()
             .filter_map(|p| {
@@ -68,43 +166,5 @@ pub async fn generate_newsletter(
             .join("\n"),
         Some(Value::String(s)) => s.clone(),
         _ => String::new(),
-    };
-    Ok(text)
-}
-
-fn build_prompt(config: &NewsletterConfig) -> String {
-    let mut parts = vec![
-        format!("Write a daily newsletter with title: {}", config.title),
-        format!("Topics: {}", config.topics.join(", ")),
-        format!("Tone: {}", config.tone),
-        format!("Length: {}", config.length),
-    ];
-    let features = config.features.as_object();
-    if let Some(feats) = features {
-        for (key, v) in feats {
-            let enabled = v.get("enabled").and_then(|e| e.as_bool()).unwrap_or(false);
-            if !enabled {
-                continue;
-            }
-            let custom: String = v
-                .get("custom_request")
-                .and_then(|c| c.as_str())
-                .unwrap_or("")
-                .to_string();
-            let label = key.replace('_', " ");
-            let label = label.split_whitespace().map(|s| {
-                let mut c = s.chars();
-                match c.next() {
-                    None => String::new(),
-                    Some(f) => f.to_uppercase().chain(c).collect(),
-                }
-            }).collect::<Vec<_>>().join(" ");
-            if custom.is_empty() {
-                parts.push(format!("Include a section: {}.", label));
-            } else {
-                parts.push(format!("Include a section: {}. User instructions for this section: {}", label, custom));
-            }
-        }
     }
-    parts.join("\n")
 }
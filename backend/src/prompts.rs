@@ -0,0 +1,141 @@
+//! Builds the generation prompt from a versioned template stored in prompt_template.
+//! Falls back to the built-in wording when no template row exists.
+
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::models::{NewsletterConfig, NewsletterSection, PromptTemplate};
+use crate::store::Store;
+
+/// Rendered request for the gateway plus the template version it came from.
+pub struct Prompt {
+    pub instructions: String,
+    pub input: String,
+    /// None when the built-in template was used.
+    pub template_version: Option<i32>,
+}
+
+const BUILTIN_INSTRUCTIONS: &str = "You are a newsletter writer. Produce a single newsletter document. Include only the sections the user requested. Follow their per-section instructions. Output plain text or markdown suitable for email.";
+const BUILTIN_HEADER: &str = "Write a daily newsletter with title: {title}\nTopics: {topics}\nTone: {tone}\nLength: {length}";
+const BUILTIN_SECTION: &str = "Include a section: {name}.";
+const BUILTIN_SECTION_INSTRUCTIONS: &str = " User instructions for this section: {instructions}";
+
+/// Loads the newsletter's pinned (or latest) template and its section catalog, then renders.
+pub async fn build_for(store: &Store, config: &NewsletterConfig) -> Result<Prompt, AppError> {
+    let sections = store.list_sections_for_user(config.user_id).await?;
+    let template = store
+        .get_prompt_template(config.prompt_template_version)
+        .await?;
+    if template.is_none() {
+        if let Some(v) = config.prompt_template_version {
+            return Err(AppError::validation(format!("Prompt template version {} not found", v)));
+        }
+    }
+    Ok(render(template.as_ref(), config, &sections))
+}
+
+/// Enabled sections are emitted in catalog order; keys missing from the catalog fall back to a
+/// title-cased label after the known ones.
+pub fn render(
+    template: Option<&PromptTemplate>,
+    config: &NewsletterConfig,
+    sections: &[NewsletterSection],
+) -> Prompt {
+    let (instructions, header, section_tpl, section_instr_tpl) = match template {
+        Some(t) => (
+            t.system_instructions.as_str(),
+            t.header_template.as_str(),
+            t.section_template.as_str(),
+            t.section_instructions_template.as_str(),
+        ),
+        None => (
+            BUILTIN_INSTRUCTIONS,
+            BUILTIN_HEADER,
+            BUILTIN_SECTION,
+            BUILTIN_SECTION_INSTRUCTIONS,
+        ),
+    };
+    let guidance = |field: fn(&PromptTemplate) -> &Value, key: &str| -> Option<String> {
+        template
+            .and_then(|t| field(t).get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    let mut parts = vec![header
+        .replace("{title}", &config.title)
+        .replace("{topics}", &config.topics.join(", "))
+        .replace("{tone}", &config.tone)
+        .replace("{length}", &config.length)];
+    if let Some(g) = guidance(|t| &t.tone_guidance, &config.tone) {
+        parts.push(g);
+    }
+    if let Some(g) = guidance(|t| &t.length_guidance, &config.length) {
+        parts.push(g);
+    }
+
+    let section_line = |key: &str, name: &str, instructions: &str| {
+        let mut line = section_tpl.replace("{name}", name);
+        if !instructions.is_empty() {
+            line.push_str(&section_instr_tpl.replace("{instructions}", instructions));
+        }
+        if let Some(snippet) = guidance(|t| &t.section_snippets, key) {
+            line.push(' ');
+            line.push_str(&snippet);
+        }
+        line
+    };
+
+    if let Some(feats) = config.features.as_object() {
+        let is_enabled = |v: &Value| v.get("enabled").and_then(|e| e.as_bool()).unwrap_or(false);
+        let custom_request = |v: &Value| {
+            v.get("custom_request")
+                .and_then(|c| c.as_str())
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        };
+        for section in sections {
+            let Some(v) = feats.get(&section.key) else {
+                continue;
+            };
+            if !is_enabled(v) {
+                continue;
+            }
+            let custom = custom_request(v);
+            let instructions = if custom.is_empty() {
+                section.default_instructions.trim()
+            } else {
+                custom.as_str()
+            };
+            parts.push(section_line(&section.key, &section.name, instructions));
+        }
+        for (key, v) in feats {
+            if !is_enabled(v) || sections.iter().any(|s| &s.key == key) {
+                continue;
+            }
+            parts.push(section_line(key, &title_case(key), &custom_request(v)));
+        }
+    }
+
+    Prompt {
+        instructions: instructions.to_string(),
+        input: parts.join("\n"),
+        template_version: template.map(|t| t.version),
+    }
+}
+
+fn title_case(key: &str) -> String {
+    key.replace('_', " ")
+        .split_whitespace()
+        .map(|s| {
+            let mut c = s.chars();
+            match c.next() {
+                None => String::new(),
+                Some(f) => f.to_uppercase().chain(c).collect(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
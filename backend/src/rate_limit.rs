@@ -0,0 +1,145 @@
+//! In-memory sliding-window rate limiter keyed by user id, and the per-user quotas built on it.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+pub struct RateLimiter {
+    max: usize,
+    window: Duration,
+    hits: Mutex<HashMap<Uuid, VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records a hit for `user_id`, or returns how long until the next one is allowed.
+    pub fn check(&self, user_id: Uuid) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = hits.entry(user_id).or_default();
+        while entry
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            entry.pop_front();
+        }
+        if entry.len() >= self.max {
+            let oldest = entry.front().copied().unwrap_or(now);
+            return Err(self.window.saturating_sub(now.duration_since(oldest)));
+        }
+        entry.push_back(now);
+        Ok(())
+    }
+
+    /// Takes back the latest hit of `user_id`, for a checked action that did not go ahead.
+    pub fn release(&self, user_id: Uuid) {
+        if let Some(entry) = self.hits.lock().unwrap_or_else(|e| e.into_inner()).get_mut(&user_id) {
+            entry.pop_back();
+        }
+    }
+
+    /// Hits of `user_id` in the current window.
+    pub fn usage(&self, user_id: Uuid) -> Usage {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = hits.entry(user_id).or_default();
+        while entry
+            .front()
+            .is_some_and(|t| now.duration_since(*t) >= self.window)
+        {
+            entry.pop_front();
+        }
+        Usage {
+            used: entry.len(),
+            limit: self.max,
+            window: self.window,
+            // When the oldest hit leaves the window, freeing one.
+            resets_in: entry
+                .front()
+                .map(|oldest| self.window.saturating_sub(now.duration_since(*oldest))),
+        }
+    }
+}
+
+pub struct Usage {
+    pub used: usize,
+    pub limit: usize,
+    pub window: Duration,
+    pub resets_in: Option<Duration>,
+}
+
+impl Usage {
+    pub fn into_api_response(self) -> serde_json::Value {
+        serde_json::json!({
+            "used": self.used,
+            "limit": self.limit,
+            "window_seconds": self.window.as_secs(),
+            "resets_in_seconds": self.resets_in.map(|d| d.as_secs().max(1)),
+        })
+    }
+}
+
+/// Records a hit on each limiter for `user_id`. If one is at its limit, the hits already
+/// recorded are taken back and that limiter, named for the message, is reported as a 429.
+pub fn check_all(user_id: Uuid, limits: &[(&RateLimiter, &str)]) -> Result<(), AppError> {
+    for (i, (limiter, name)) in limits.iter().enumerate() {
+        if let Err(wait) = limiter.check(user_id) {
+            for (taken, _) in &limits[..i] {
+                taken.release(user_id);
+            }
+            return Err(AppError::RateLimited {
+                message: format!("{} limit reached. Try again in {} seconds.", name, wait.as_secs().max(1)),
+                retry_after: Some(wait),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Per-user limits on what costs money: newsletters kept (MAX_NEWSLETTERS_PER_USER), previews
+/// and sample sends per hour (PREVIEW_RATE_LIMIT_PER_HOUR, SAMPLE_RATE_LIMIT_PER_HOUR), and
+/// model generations per day of any kind: previews, samples, run-now and scheduled issues
+/// (GENERATION_LIMIT_PER_DAY).
+#[derive(Clone)]
+pub struct Quotas {
+    pub max_newsletters: u64,
+    pub previews: Arc<RateLimiter>,
+    pub samples: Arc<RateLimiter>,
+    pub generations: Arc<RateLimiter>,
+}
+
+impl Quotas {
+    pub fn from_env() -> Self {
+        let limit = |name: &str, default: usize| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(default)
+        };
+        Quotas {
+            max_newsletters: limit("MAX_NEWSLETTERS_PER_USER", 25) as u64,
+            previews: Arc::new(RateLimiter::new(
+                limit("PREVIEW_RATE_LIMIT_PER_HOUR", 20),
+                Duration::from_secs(60 * 60),
+            )),
+            samples: Arc::new(RateLimiter::new(
+                limit("SAMPLE_RATE_LIMIT_PER_HOUR", 10),
+                Duration::from_secs(60 * 60),
+            )),
+            generations: Arc::new(RateLimiter::new(
+                limit("GENERATION_LIMIT_PER_DAY", 50),
+                Duration::from_secs(24 * 60 * 60),
+            )),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::auth::User;
+use crate::error::AppError;
+use crate::models::CreateAccessRequest;
+use crate::store::Store;
+
+/// Maximum length of the free-text note attached to a request.
+const MAX_MESSAGE_LEN: usize = 2000;
+
+/// Files an access request for the signed-in user. Returns the open request if one exists.
+#[rocket::post("/me/access-request", data = "<body>")]
+pub async fn create(
+    user: User,
+    store: &State<Store>,
+    body: Option<Json<CreateAccessRequest>>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if store.is_user_approved(user.0.user_id).await? {
+        return Err(AppError::Conflict("User is already approved".into()));
+    }
+    let latest = store.latest_access_request(user.0.user_id).await?;
+    if let Some(existing) = latest.filter(|r| r.status == "pending") {
+        return Ok(Json(existing.into_api_response()));
+    }
+    let email = user
+        .0
+        .email
+        .as_deref()
+        .ok_or_else(|| AppError::BadRequest("Account has no email address".into()))?;
+    let message = body
+        .as_ref()
+        .and_then(|b| b.message.as_deref())
+        .unwrap_or("")
+        .trim();
+    if message.len() > MAX_MESSAGE_LEN {
+        return Err(AppError::validation(format!(
+            "message must be at most {} characters",
+            MAX_MESSAGE_LEN
+        )));
+    }
+    let request = store
+        .create_access_request(user.0.user_id, email, message)
+        .await?;
+    Ok(Json(request.into_api_response()))
+}
+
+#[rocket::get("/me/access-request")]
+pub async fn get(user: User, store: &State<Store>) -> Result<Json<serde_json::Value>, AppError> {
+    let request = store
+        .latest_access_request(user.0.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No access request".into()))?;
+    Ok(Json(request.into_api_response()))
+}
@@ -0,0 +1,183 @@
+use rocket::http::Status;
+use rocket::response::status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::auth::AdminUser;
+use crate::email::{self, EmailConfig};
+use crate::error::AppError;
+use crate::jobs::JobQueue;
+use crate::routes::newsletters;
+use crate::store::Store;
+
+#[rocket::get("/admin/access-requests?<status>")]
+pub async fn list_access_requests(
+    _admin: AdminUser,
+    store: &State<Store>,
+    status: Option<&str>,
+) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    if status.is_some_and(|s| !matches!(s, "pending" | "approved" | "rejected")) {
+        return Err(AppError::BadRequest(
+            "status must be pending, approved or rejected".into(),
+        ));
+    }
+    let requests = store.list_access_requests(status).await?;
+    Ok(Json(requests.into_iter().map(|r| r.into_api_response()).collect()))
+}
+
+/// Approves a pending request: adds the user to approved_users and emails them. The user is
+/// approved before the request is marked, so a failure in between leaves it pending to retry;
+/// approving an already-approved request re-applies the approval and returns it.
+#[rocket::post("/admin/access-requests/<id>/approve")]
+pub async fn approve_access_request(
+    admin: AdminUser,
+    store: &State<Store>,
+    email_config: &State<EmailConfig>,
+    id: &str,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let id = AppError::parse_id(id)?;
+    let request = store
+        .get_access_request(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No access request with that id".into()))?;
+    if request.status == "rejected" {
+        return Err(AppError::Conflict("Access request was rejected".into()));
+    }
+    store
+        .approve_user(request.user_id, Some(admin.0.user_id))
+        .await?;
+    if request.status == "approved" {
+        return Ok(Json(request.into_api_response()));
+    }
+    let request = store
+        .review_access_request(id, "approved", admin.0.user_id)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Access request was reviewed meanwhile".into()))?;
+    // Approval stands even if the notification cannot be delivered.
+    if let Err(e) = email::send_access_approved(email_config.inner(), &request.email).await {
+        eprintln!("[admin] approval email to {} failed: {}", request.email, e);
+    }
+    Ok(Json(request.into_api_response()))
+}
+
+#[rocket::post("/admin/access-requests/<id>/reject")]
+pub async fn reject_access_request(
+    admin: AdminUser,
+    store: &State<Store>,
+    id: &str,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let id = AppError::parse_id(id)?;
+    let request = store
+        .review_access_request(id, "rejected", admin.0.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No pending access request with that id".into()))?;
+    Ok(Json(request.into_api_response()))
+}
+
+#[rocket::get("/admin/users")]
+pub async fn list_users(_admin: AdminUser, store: &State<Store>) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let users = store.list_approved_users().await?;
+    Ok(Json(
+        users
+            .into_iter()
+            .map(|u| {
+                serde_json::json!({
+                    "user_id": u.user_id,
+                    "approved_at": u.approved_at.to_rfc3339(),
+                    "approved_by": u.approved_by,
+                })
+            })
+            .collect(),
+    ))
+}
+
+/// Grants access directly, without a pending request.
+#[rocket::put("/admin/users/<user_id>/approval")]
+pub async fn approve_user(admin: AdminUser, store: &State<Store>, user_id: &str) -> Result<Status, AppError> {
+    let user_id = AppError::parse_id(user_id)?;
+    store.approve_user(user_id, Some(admin.0.user_id)).await?;
+    Ok(Status::NoContent)
+}
+
+#[rocket::delete("/admin/users/<user_id>/approval")]
+pub async fn revoke_user(_admin: AdminUser, store: &State<Store>, user_id: &str) -> Result<Status, AppError> {
+    let user_id = AppError::parse_id(user_id)?;
+    if store.revoke_user(user_id).await? {
+        Ok(Status::NoContent)
+    } else {
+        Err(AppError::NotFound("User is not approved".into()))
+    }
+}
+
+#[rocket::get("/admin/newsletters?<user_id>&<active>")]
+pub async fn list_newsletters(
+    _admin: AdminUser,
+    store: &State<Store>,
+    user_id: Option<&str>,
+    active: Option<bool>,
+) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let user_id = user_id.map(AppError::parse_id).transpose()?;
+    let configs = store.list_all_newsletters(user_id, active).await?;
+    Ok(Json(newsletters::api_responses(store, configs).await?))
+}
+
+#[rocket::get("/admin/run-logs?<newsletter_id>&<status>&<limit>")]
+pub async fn list_run_logs(
+    _admin: AdminUser,
+    store: &State<Store>,
+    newsletter_id: Option<&str>,
+    status: Option<&str>,
+    limit: Option<u32>,
+) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let newsletter_id = newsletter_id.map(AppError::parse_id).transpose()?;
+    if status.is_some_and(|s| !matches!(s, "success" | "failure" | "skipped")) {
+        return Err(AppError::BadRequest("status must be success, failure or skipped".into()));
+    }
+    let logs = store
+        .list_run_logs(newsletter_id, status, limit.unwrap_or(100).min(1000))
+        .await?;
+    Ok(Json(logs.into_iter().map(|l| l.into_api_response()).collect()))
+}
+
+async fn set_active(store: &Store, id: &str, active: bool) -> Result<Json<serde_json::Value>, AppError> {
+    let id = AppError::parse_id(id)?;
+    let config = store
+        .set_newsletter_active(id, active)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+    Ok(Json(newsletters::api_response(store, config).await?))
+}
+
+#[rocket::post("/admin/newsletters/<id>/pause")]
+pub async fn pause_newsletter(_admin: AdminUser, store: &State<Store>, id: &str) -> Result<Json<serde_json::Value>, AppError> {
+    set_active(store, id, false).await
+}
+
+#[rocket::post("/admin/newsletters/<id>/resume")]
+pub async fn resume_newsletter(_admin: AdminUser, store: &State<Store>, id: &str) -> Result<Json<serde_json::Value>, AppError> {
+    set_active(store, id, true).await
+}
+
+/// Queues a scheduled run now, regardless of send time or whether today's issue went out.
+#[rocket::post("/admin/newsletters/<id>/run")]
+pub async fn force_run(
+    admin: AdminUser,
+    store: &State<Store>,
+    queue: &State<JobQueue>,
+    id: &str,
+) -> Result<status::Accepted<Json<serde_json::Value>>, AppError> {
+    let id = AppError::parse_id(id)?;
+    let config = store
+        .get_newsletter_any(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+    eprintln!("[admin] {} forced run of newsletter {}", admin.0.user_id, id);
+    let job_id = queue
+        .enqueue_scheduled_run(config)
+        .await?
+        .ok_or_else(|| AppError::Conflict("A run for this newsletter is already queued or running".into()))?;
+    Ok(status::Accepted(Json(serde_json::json!({
+        "job_id": job_id,
+        "status": "queued",
+    }))))
+}
@@ -0,0 +1,92 @@
+use chrono::{Duration, Utc};
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::auth::{self, ApprovedUser};
+use crate::error::AppError;
+use crate::models::CreateApiToken;
+use crate::store::Store;
+
+/// Token management needs a browser session; a token cannot mint or revoke tokens.
+fn require_session(user: &ApprovedUser) -> Result<(), AppError> {
+    if user.0.token_scopes.is_some() {
+        return Err(AppError::Forbidden(
+            "API tokens cannot be managed with an API token".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Longest lifetime a token can be given; omit `expires_in_days` for one that never expires.
+const MAX_EXPIRES_IN_DAYS: u32 = 3650;
+
+/// Creates a token and returns it in plain text; it cannot be retrieved again.
+#[rocket::post("/me/tokens", data = "<body>")]
+pub async fn create(
+    user: ApprovedUser,
+    store: &State<Store>,
+    body: Json<CreateApiToken>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    require_session(&user)?;
+    let name = body.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::validation("name must be 1-100 characters"));
+    }
+    let mut scopes = body.scopes.clone().unwrap_or_else(|| vec!["read".into()]);
+    scopes.sort();
+    scopes.dedup();
+    if scopes.is_empty() || scopes.iter().any(|s| s != "read" && s != "write") {
+        return Err(AppError::validation(
+            "scopes must be a non-empty list of \"read\" and/or \"write\"",
+        ));
+    }
+    let expires_at = match body.expires_in_days {
+        Some(days) if !(1..=MAX_EXPIRES_IN_DAYS).contains(&days) => {
+            return Err(AppError::validation(format!(
+                "expires_in_days must be between 1 and {}",
+                MAX_EXPIRES_IN_DAYS
+            )))
+        }
+        Some(days) => Some(
+            Utc::now()
+                .checked_add_signed(Duration::days(days.into()))
+                .ok_or_else(|| AppError::validation("expires_in_days is out of range"))?,
+        ),
+        None => None,
+    };
+
+    let token = auth::generate_api_token();
+    let prefix: String = token.chars().take(auth::API_TOKEN_PREFIX.len() + 6).collect();
+    let created = store
+        .create_api_token(
+            user.0.user_id,
+            name,
+            &auth::hash_api_token(&token),
+            &prefix,
+            &scopes,
+            expires_at,
+        )
+        .await?;
+    let mut out = created.into_api_response();
+    out["token"] = serde_json::json!(token);
+    Ok(Json(out))
+}
+
+#[rocket::get("/me/tokens")]
+pub async fn list(user: ApprovedUser, store: &State<Store>) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    require_session(&user)?;
+    let tokens = store.list_api_tokens(user.0.user_id).await?;
+    Ok(Json(tokens.into_iter().map(|t| t.into_api_response()).collect()))
+}
+
+#[rocket::delete("/me/tokens/<id>")]
+pub async fn revoke(user: ApprovedUser, store: &State<Store>, id: &str) -> Result<Status, AppError> {
+    require_session(&user)?;
+    let id = AppError::parse_id(id)?;
+    if store.revoke_api_token(id, user.0.user_id).await? {
+        Ok(Status::NoContent)
+    } else {
+        Err(AppError::NotFound("Token not found".into()))
+    }
+}
@@ -0,0 +1,251 @@
+//! A user's newsletters as a versioned JSON or YAML bundle (see `NewsletterBundle`), for keeping
+//! them in version control. Import checks every entry before writing anything.
+
+use rocket::data::{Data, ToByteUnit};
+use rocket::http::{ContentType, Header};
+use rocket::serde::json::Json;
+use rocket::State;
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use crate::auth::ApprovedUser;
+use crate::error::AppError;
+use crate::models::{CreateNewsletterConfig, NewsletterBundle, NewsletterConfig, UpdateNewsletterConfig};
+use crate::rate_limit::Quotas;
+use crate::routes::newsletters::check_newsletter_quota;
+use crate::routes::sections;
+use crate::store::{self, Store};
+use crate::validation::{self, FieldErrors};
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// `?format=` wins; otherwise a YAML content type selects YAML and anything else JSON.
+    fn pick(format: Option<&str>, content_type: Option<&ContentType>) -> Result<Self, AppError> {
+        match format {
+            Some("json") => Ok(Format::Json),
+            Some("yaml") | Some("yml") => Ok(Format::Yaml),
+            Some(other) => Err(AppError::BadRequest(format!("format must be json or yaml (got {})", other))),
+            None if content_type.is_some_and(|c| c.sub().as_str().contains("yaml")) => Ok(Format::Yaml),
+            None => Ok(Format::Json),
+        }
+    }
+}
+
+/// A bundle download; `Content-Disposition` suggests a file name.
+#[derive(rocket::Responder)]
+pub struct Export {
+    body: (ContentType, String),
+    disposition: Header<'static>,
+}
+
+fn export(configs: &[NewsletterConfig], format: Format, name: &str) -> Result<Export, AppError> {
+    let bundle = NewsletterBundle::new(configs.iter().map(NewsletterConfig::to_create_body).collect());
+    let (content_type, text, ext) = match format {
+        Format::Json => (
+            ContentType::JSON,
+            serde_json::to_string_pretty(&bundle).map_err(|e| AppError::Internal(e.to_string()))?,
+            "json",
+        ),
+        Format::Yaml => (
+            ContentType::new("application", "yaml"),
+            serde_yaml::to_string(&bundle).map_err(|e| AppError::Internal(e.to_string()))?,
+            "yaml",
+        ),
+    };
+    Ok(Export {
+        body: (content_type, text),
+        disposition: Header::new(
+            "Content-Disposition",
+            format!("attachment; filename=\"{}.{}\"", name, ext),
+        ),
+    })
+}
+
+#[rocket::get("/me/newsletters/export?<format>")]
+pub async fn export_all(user: ApprovedUser, store: &State<Store>, format: Option<&str>) -> Result<Export, AppError> {
+    let format = Format::pick(format, None)?;
+    let configs = store::all_newsletters_by_user(store, user.0.user_id).await?;
+    export(&configs, format, "newsletters")
+}
+
+#[rocket::get("/me/newsletters/<id>/export?<format>")]
+pub async fn export_one(
+    user: ApprovedUser,
+    store: &State<Store>,
+    id: &str,
+    format: Option<&str>,
+) -> Result<Export, AppError> {
+    let format = Format::pick(format, None)?;
+    let id = AppError::parse_id(id)?;
+    let config = store
+        .get_newsletter_by_id(id, user.0.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+    export(&[config], format, &format!("newsletter-{}", id))
+}
+
+/// Query string of an import. `mode` is `create` (every entry becomes a new newsletter, the
+/// default) or `upsert` (an entry updates the newsletter with the same title, if there is one).
+#[derive(rocket::FromForm)]
+pub struct ImportParams<'r> {
+    format: Option<&'r str>,
+    mode: Option<&'r str>,
+    dry_run: Option<bool>,
+}
+
+enum Action {
+    Create,
+    Update(Uuid),
+}
+
+/// Imports a bundle. Every entry is checked first; if any fails, nothing is written and the 422
+/// lists per-entry errors. With `dry_run=true` the plan is reported without writing either way.
+#[rocket::post("/me/newsletters/import?<params..>", data = "<data>")]
+pub async fn import(
+    user: ApprovedUser,
+    store: &State<Store>,
+    quotas: &State<Quotas>,
+    content_type: Option<&ContentType>,
+    params: ImportParams<'_>,
+    data: Data<'_>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user_id = user.0.user_id;
+    let format = Format::pick(params.format, content_type)?;
+    let upsert = match params.mode {
+        None | Some("create") => false,
+        Some("upsert") => true,
+        Some(other) => return Err(AppError::BadRequest(format!("mode must be create or upsert (got {})", other))),
+    };
+    let dry_run = params.dry_run.unwrap_or(false);
+
+    let text = data
+        .open(1.mebibytes())
+        .into_string()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Could not read body: {}", e)))?;
+    if !text.is_complete() {
+        return Err(AppError::BadRequest("Import is larger than 1 MiB".into()));
+    }
+    let bundle: NewsletterBundle = match format {
+        Format::Json => serde_json::from_str(&text).map_err(|e| AppError::BadRequest(format!("Invalid JSON: {}", e)))?,
+        Format::Yaml => serde_yaml::from_str(&text).map_err(|e| AppError::BadRequest(format!("Invalid YAML: {}", e)))?,
+    };
+    if bundle.version != NewsletterBundle::VERSION {
+        return Err(AppError::BadRequest(format!(
+            "Unsupported bundle version {}; expected {}",
+            bundle.version,
+            NewsletterBundle::VERSION
+        )));
+    }
+
+    let sections = store.list_sections_for_user(user_id).await?;
+    let mut by_title: HashMap<String, Vec<Uuid>> = HashMap::new();
+    if upsert {
+        for c in store::all_newsletters_by_user(store, user_id).await? {
+            by_title.entry(c.title).or_default().push(c.id);
+        }
+    }
+    let mut seen_titles = HashSet::new();
+    let mut plan = Vec::new();
+    let mut report = Vec::new();
+    let mut invalid = 0;
+    for (index, item) in bundle.newsletters.iter().enumerate() {
+        let mut errors = FieldErrors::default();
+        validation::check_newsletter_create(&mut errors, item);
+        if let Some(f) = item.features.as_ref() {
+            sections::validate_features(&mut errors, f, &sections);
+        }
+        if let Some(v) = item.prompt_template_version {
+            if store.get_prompt_template(Some(v)).await?.is_none() {
+                errors.add("prompt_template_version", format!("unknown version {}", v));
+            }
+        }
+        let mut action = Action::Create;
+        if upsert {
+            match item.title.as_deref() {
+                // Already reported as required by `check_newsletter_create`.
+                None => {}
+                Some(title) if !seen_titles.insert(title) => errors.add("title", "appears more than once in this import"),
+                Some(title) => match by_title.get(title).map(Vec::as_slice) {
+                    None | Some([]) => {}
+                    Some([id]) => action = Action::Update(*id),
+                    Some(ids) => errors.add("title", format!("matches {} existing newsletters", ids.len())),
+                },
+            }
+        }
+        if matches!(action, Action::Create) && item.delivery_email.is_none() && user.0.email.is_none() {
+            errors.add("delivery_email", "is required");
+        }
+        let (name, id) = match (&action, errors.is_empty()) {
+            (_, false) => ("invalid", None),
+            (Action::Create, true) => ("create", None),
+            (Action::Update(id), true) => ("update", Some(*id)),
+        };
+        if !errors.is_empty() {
+            invalid += 1;
+        }
+        report.push(serde_json::json!({
+            "index": index,
+            "title": item.title,
+            "action": name,
+            "id": id,
+            "errors": if errors.is_empty() { serde_json::Value::Null } else { errors.into_json() },
+        }));
+        plan.push(action);
+    }
+    if invalid > 0 {
+        return Err(AppError::Validation {
+            message: format!(
+                "{} of {} newsletters are invalid; nothing was imported",
+                invalid,
+                bundle.newsletters.len()
+            ),
+            details: serde_json::json!({ "items": report }),
+        });
+    }
+
+    let creates = plan.iter().filter(|a| matches!(a, Action::Create)).count();
+    check_newsletter_quota(store, quotas, user_id, creates as u64).await?;
+
+    let (mut created, mut updated) = (0, 0);
+    for ((item, action), entry) in bundle.newsletters.iter().zip(&plan).zip(report.iter_mut()) {
+        match action {
+            Action::Create => {
+                created += 1;
+                if !dry_run {
+                    let config = create(store, &user, item).await?;
+                    entry["id"] = serde_json::json!(config.id);
+                }
+            }
+            Action::Update(id) => {
+                updated += 1;
+                if !dry_run {
+                    store
+                        .update_newsletter(*id, user_id, &UpdateNewsletterConfig::from(item), None)
+                        .await?
+                        .ok_or_else(|| AppError::Conflict(format!("Newsletter {} was deleted during the import", id)))?;
+                }
+            }
+        }
+    }
+    Ok(Json(serde_json::json!({
+        "dry_run": dry_run,
+        "created": created,
+        "updated": updated,
+        "items": report,
+    })))
+}
+
+async fn create(store: &Store, user: &ApprovedUser, item: &CreateNewsletterConfig) -> Result<NewsletterConfig, AppError> {
+    let email = item
+        .delivery_email
+        .as_deref()
+        .or(user.0.email.as_deref())
+        .ok_or_else(|| AppError::validation("delivery_email is required"))?;
+    store.create_newsletter(user.0.user_id, email, item).await
+}
@@ -0,0 +1,16 @@
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::auth::ApprovedUser;
+use crate::error::AppError;
+use crate::store::Store;
+
+#[rocket::get("/jobs/<id>")]
+pub async fn get(user: ApprovedUser, store: &State<Store>, id: &str) -> Result<Json<serde_json::Value>, AppError> {
+    let id = AppError::parse_id(id)?;
+    let job = store
+        .get_job(id, user.0.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Job not found".into()))?;
+    Ok(Json(job.into_api_response()))
+}
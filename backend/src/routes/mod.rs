@@ -1,2 +1,11 @@
+pub mod access_requests;
+pub mod admin;
+pub mod api_tokens;
 pub mod health;
+pub mod import_export;
+pub mod jobs;
 pub mod newsletters;
+pub mod presets;
+pub mod prompt_templates;
+pub mod sections;
+pub mod usage;
@@ -1,123 +1,518 @@
-use chrono::Utc;
-use rocket::http::Status;
+use chrono::{DateTime, Duration, Utc};
+use rocket::data::{Data, ToByteUnit};
+use rocket::http::{Header, Status};
+use rocket::request::{FromRequest, Outcome, Request};
+use rocket::response::{self, status, Responder, Response};
+use rocket::response::stream::{Event, EventStream};
 use rocket::serde::json::Json;
 use rocket::State;
+use std::collections::HashMap;
 use uuid::Uuid;
 
 use crate::auth::{ApprovedUser, User};
-use crate::email::{self, EmailConfig};
-use crate::models::{CreateNewsletterConfig, UpdateNewsletterConfig};
+use crate::calendar;
+use crate::email;
+use crate::error::AppError;
+use crate::jobs::{JobQueue, SampleProgress};
+use crate::models::{CreateNewsletterConfig, NewsletterConfig, PatchNewsletterConfig, RunLog, UpdateNewsletterConfig};
 use crate::openclaw_client::{self, OpenClawConfig};
-use crate::supabase::SupabaseClient;
+use crate::prompts;
+use crate::rate_limit::{self, Quotas};
+use crate::routes::sections;
+use crate::scheduler;
+use crate::store::{self, NewsletterCursor, NewsletterListQuery, NewsletterSort, Store};
+use crate::validation::{self, FieldErrors};
+
+/// `If-Match` precondition on newsletter writes, carrying an ETag from GET or PUT.
+pub enum IfMatch {
+    /// No header, or `*`: write unconditionally.
+    Any,
+    /// Write only if the newsletter still has this `updated_at`.
+    UpdatedAt(DateTime<Utc>),
+    /// A tag this server never issued (e.g. a weak one); it never matches.
+    Unknown,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = ();
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let tag = match req.headers().get_one("If-Match").map(str::trim) {
+            None | Some("*") => return Outcome::Success(IfMatch::Any),
+            Some(t) => t,
+        };
+        let updated_at = tag
+            .strip_prefix('"')
+            .and_then(|t| t.strip_suffix('"'))
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+        Outcome::Success(match updated_at {
+            Some(t) => IfMatch::UpdatedAt(t.with_timezone(&Utc)),
+            None => IfMatch::Unknown,
+        })
+    }
+}
+
+/// A newsletter response with its `ETag` header.
+#[derive(rocket::Responder)]
+pub struct TaggedNewsletter {
+    body: Json<serde_json::Value>,
+    etag: Header<'static>,
+}
+
+impl TaggedNewsletter {
+    /// Looks up the newsletter's latest run for `last_run` and `next_run_at`.
+    pub async fn load(store: &Store, config: NewsletterConfig) -> Result<Self, AppError> {
+        let etag = Header::new("ETag", config.etag());
+        Ok(TaggedNewsletter {
+            body: Json(api_response(store, config).await?),
+            etag,
+        })
+    }
+}
+
+/// For a newsletter that was just created, so has no runs yet.
+impl From<NewsletterConfig> for TaggedNewsletter {
+    fn from(config: NewsletterConfig) -> Self {
+        let etag = Header::new("ETag", config.etag());
+        let next_run_at = scheduler::next_run_at(&config, None, Utc::now());
+        TaggedNewsletter {
+            body: Json(config.into_api_response(None, next_run_at)),
+            etag,
+        }
+    }
+}
+
+/// Latest run of each of `configs` that has run, by newsletter id, in one lookup.
+async fn last_runs(store: &Store, configs: &[NewsletterConfig]) -> Result<HashMap<Uuid, RunLog>, AppError> {
+    let ids: Vec<Uuid> = configs.iter().map(|c| c.id).collect();
+    Ok(store
+        .latest_run_logs(&ids)
+        .await?
+        .into_iter()
+        .map(|l| (l.newsletter_config_id, l))
+        .collect())
+}
+
+/// API responses of `configs`, in order, with their latest runs. The latest run decides
+/// whether today's issue is still to come in `next_run_at`.
+pub async fn api_responses(store: &Store, configs: Vec<NewsletterConfig>) -> Result<Vec<serde_json::Value>, AppError> {
+    let last_runs = last_runs(store, &configs).await?;
+    let now = Utc::now();
+    Ok(configs
+        .into_iter()
+        .map(|c| {
+            let last_run = last_runs.get(&c.id);
+            let next_run_at = scheduler::next_run_at(&c, last_run.map(|r| r.run_at), now);
+            c.into_api_response(last_run, next_run_at)
+        })
+        .collect())
+}
+
+pub async fn api_response(store: &Store, config: NewsletterConfig) -> Result<serde_json::Value, AppError> {
+    let mut out = api_responses(store, vec![config]).await?;
+    Ok(out.remove(0))
+}
+
+/// A page of newsletters, with `X-Total-Count` and, when there is more, `X-Next-Cursor`.
+pub struct NewsletterList {
+    items: Vec<serde_json::Value>,
+    total: u64,
+    next_cursor: Option<String>,
+}
+
+impl<'r> Responder<'r, 'static> for NewsletterList {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let mut res = Response::build_from(Json(self.items).respond_to(req)?);
+        res.header(Header::new("X-Total-Count", self.total.to_string()));
+        if let Some(cursor) = self.next_cursor {
+            res.header(Header::new("X-Next-Cursor", cursor));
+        }
+        res.ok()
+    }
+}
+
+pub const DEFAULT_PAGE_SIZE: u32 = 50;
+pub const MAX_PAGE_SIZE: u32 = 200;
+
+/// 409 with the newsletter's current state after a failed `If-Match`, or 404 if it is gone.
+async fn stale(store: &Store, id: Uuid, user_id: Uuid) -> AppError {
+    let current = match store.get_newsletter_by_id(id, user_id).await {
+        Ok(Some(current)) => current,
+        Ok(None) => return AppError::NotFound("Newsletter not found".into()),
+        Err(e) => return e,
+    };
+    let etag = current.etag();
+    match api_response(store, current).await {
+        Ok(current) => AppError::Stale {
+            message: "Newsletter was changed since it was loaded; reload and try again".into(),
+            etag,
+            current,
+        },
+        Err(e) => e,
+    }
+}
+
+/// A pinned prompt template version must exist.
+pub async fn check_prompt_template_version(
+    store: &Store,
+    version: Option<i32>,
+) -> Result<(), AppError> {
+    let Some(v) = version else {
+        return Ok(());
+    };
+    if store.get_prompt_template(Some(v)).await?.is_none() {
+        return Err(AppError::validation(format!("Unknown prompt_template_version: {}", v)));
+    }
+    Ok(())
+}
+
+/// 409 if `adding` more newsletters would take the user past MAX_NEWSLETTERS_PER_USER. Unlike
+/// the rate limits this is a count, not a window: waiting frees nothing, so there is no 429 or
+/// `Retry-After`, only a conflict with the newsletters the user already has.
+pub async fn check_newsletter_quota(
+    store: &Store,
+    quotas: &Quotas,
+    user_id: Uuid,
+    adding: u64,
+) -> Result<(), AppError> {
+    let count = store::count_newsletters_by_user(store, user_id).await?;
+    if count + adding > quotas.max_newsletters {
+        return Err(AppError::Conflict(format!(
+            "You have {} of at most {} newsletters; delete one to make room",
+            count, quotas.max_newsletters
+        )));
+    }
+    Ok(())
+}
 
 #[rocket::get("/me/approval-status")]
 pub async fn approval_status(
     user: User,
-    supabase: &State<SupabaseClient>,
-) -> Result<Json<serde_json::Value>, Status> {
-    let approved = supabase
-        .is_user_approved(user.0.user_id)
-        .await
-        .map_err(|_| Status::InternalServerError)?;
-    Ok(Json(serde_json::json!({ "approved": approved })))
+    store: &State<Store>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let approved = store.is_user_approved(user.0.user_id).await?;
+    let access_request = if approved {
+        None
+    } else {
+        store
+            .latest_access_request(user.0.user_id)
+            .await?
+            .map(|r| r.status)
+    };
+    Ok(Json(serde_json::json!({ "approved": approved, "access_request": access_request })))
 }
 
-#[rocket::get("/me/newsletters")]
-pub async fn list(user: ApprovedUser, supabase: &State<SupabaseClient>) -> Result<Json<Vec<serde_json::Value>>, Status> {
-    let configs = supabase
-        .list_newsletters_by_user(user.0.user_id)
-        .await
-        .map_err(|_| Status::InternalServerError)?;
-    let out: Vec<serde_json::Value> = configs.into_iter().map(|c| c.into_api_response()).collect();
-    Ok(Json(out))
+/// Query string of the newsletter list. `sort` is created (newest first), title or next_send;
+/// pass `X-Next-Cursor` back as `cursor` for the next page. `send_from` / `send_to` bound the
+/// UTC send time (HH:MM, inclusive).
+#[derive(rocket::FromForm)]
+pub struct ListParams<'r> {
+    sort: Option<&'r str>,
+    cursor: Option<&'r str>,
+    limit: Option<u32>,
+    active: Option<bool>,
+    tag: Option<&'r str>,
+    send_from: Option<&'r str>,
+    send_to: Option<&'r str>,
+}
+
+#[rocket::get("/me/newsletters?<params..>")]
+pub async fn list(
+    user: ApprovedUser,
+    store: &State<Store>,
+    params: ListParams<'_>,
+) -> Result<NewsletterList, AppError> {
+    let ListParams {
+        sort,
+        cursor,
+        limit,
+        active,
+        tag,
+        send_from,
+        send_to,
+    } = params;
+    let sort = match sort {
+        None => NewsletterSort::Created,
+        Some(s) => NewsletterSort::parse(s)
+            .ok_or_else(|| AppError::BadRequest("sort must be created, title or next_send".into()))?,
+    };
+    let after = cursor
+        .map(|c| NewsletterCursor::decode(c).filter(|c| c.sort() == sort))
+        .map(|c| c.ok_or_else(|| AppError::BadRequest("cursor is invalid or from a different sort".into())))
+        .transpose()?;
+    let send_time = |name: &str, value: Option<&str>| {
+        value
+            .map(|v| {
+                validation::parse_send_time(v)
+                    .ok_or_else(|| AppError::BadRequest(format!("{} must be a 24-hour time as HH:MM", name)))
+            })
+            .transpose()
+    };
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let query = NewsletterListQuery {
+        sort,
+        after,
+        // One extra row tells us whether there is a next page.
+        limit: limit + 1,
+        is_active: active,
+        tag: tag.map(str::to_string),
+        send_from: send_time("send_from", send_from)?,
+        send_to: send_time("send_to", send_to)?,
+    };
+    let mut page = store.list_newsletters_by_user(user.0.user_id, &query).await?;
+    let next_cursor = if page.items.len() > limit as usize {
+        page.items.truncate(limit as usize);
+        page.items.last().map(|c| sort.cursor_after(c).encode())
+    } else {
+        None
+    };
+    Ok(NewsletterList {
+        items: api_responses(store, page.items).await?,
+        total: page.total,
+        next_cursor,
+    })
 }
 
 #[rocket::post("/me/newsletters", data = "<body>")]
 pub async fn create(
     user: ApprovedUser,
-    supabase: &State<SupabaseClient>,
+    store: &State<Store>,
+    quotas: &State<Quotas>,
     body: Json<CreateNewsletterConfig>,
-) -> Result<Json<serde_json::Value>, Status> {
+) -> Result<TaggedNewsletter, AppError> {
+    validation::newsletter_create(&body)?;
+    check_newsletter_quota(store, quotas, user.0.user_id, 1).await?;
     let email = body
         .delivery_email
         .as_deref()
         .or(user.0.email.as_deref())
-        .ok_or(Status::BadRequest)?;
-    let config = supabase
+        .ok_or_else(|| AppError::validation("delivery_email is required"))?;
+    if let Some(f) = body.features.as_ref() {
+        sections::check_features(store, user.0.user_id, f).await?;
+    }
+    check_prompt_template_version(store, body.prompt_template_version).await?;
+    let config = store
         .create_newsletter(user.0.user_id, email, &body)
-        .await
-        .map_err(|_| Status::InternalServerError)?;
-    Ok(Json(config.into_api_response()))
+        .await?;
+    Ok(config.into())
 }
 
 #[rocket::get("/me/newsletters/<id>")]
-pub async fn get(user: ApprovedUser, supabase: &State<SupabaseClient>, id: &str) -> Result<Json<serde_json::Value>, Status> {
-    let id = Uuid::parse_str(id).map_err(|_| Status::BadRequest)?;
-    let config = supabase
+pub async fn get(user: ApprovedUser, store: &State<Store>, id: &str) -> Result<TaggedNewsletter, AppError> {
+    let id = AppError::parse_id(id)?;
+    let config = store
         .get_newsletter_by_id(id, user.0.user_id)
-        .await
-        .map_err(|_| Status::InternalServerError)?
-        .ok_or(Status::NotFound)?;
-    Ok(Json(config.into_api_response()))
+        .await?
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+    TaggedNewsletter::load(store, config).await
 }
 
 #[rocket::put("/me/newsletters/<id>", data = "<body>")]
 pub async fn update(
     user: ApprovedUser,
-    supabase: &State<SupabaseClient>,
+    store: &State<Store>,
     id: &str,
+    if_match: IfMatch,
     body: Json<UpdateNewsletterConfig>,
-) -> Result<Json<serde_json::Value>, Status> {
-    let id = Uuid::parse_str(id).map_err(|_| Status::BadRequest)?;
-    let config = supabase
-        .update_newsletter(id, user.0.user_id, &body)
+) -> Result<TaggedNewsletter, AppError> {
+    let id = AppError::parse_id(id)?;
+    validation::newsletter_update(&body)?;
+    if let Some(f) = body.features.as_ref() {
+        sections::check_features(store, user.0.user_id, f).await?;
+    }
+    check_prompt_template_version(store, body.prompt_template_version).await?;
+    let expected = match if_match {
+        IfMatch::Any => None,
+        IfMatch::UpdatedAt(t) => Some(t),
+        IfMatch::Unknown => return Err(stale(store, id, user.0.user_id).await),
+    };
+    match store.update_newsletter(id, user.0.user_id, &body, expected).await? {
+        Some(config) => TaggedNewsletter::load(store, config).await,
+        None if expected.is_some() => Err(stale(store, id, user.0.user_id).await),
+        None => Err(AppError::NotFound("Newsletter not found".into())),
+    }
+}
+
+/// Tries before a PATCH without `If-Match` gives up on concurrent writes.
+const PATCH_ATTEMPTS: usize = 3;
+
+/// Partial update: absent fields are kept, `null` resets one, and `features` is merged (JSON Merge
+/// Patch), so a single section can be toggled without resending the whole map.
+#[rocket::patch("/me/newsletters/<id>", data = "<body>")]
+pub async fn patch(
+    user: ApprovedUser,
+    store: &State<Store>,
+    id: &str,
+    if_match: IfMatch,
+    body: Json<PatchNewsletterConfig>,
+) -> Result<TaggedNewsletter, AppError> {
+    let id = AppError::parse_id(id)?;
+    let user_id = user.0.user_id;
+    validation::newsletter_patch(&body)?;
+    check_prompt_template_version(store, body.prompt_template_version.as_value().copied()).await?;
+    let expected = match if_match {
+        IfMatch::Any => None,
+        IfMatch::UpdatedAt(t) => Some(t),
+        IfMatch::Unknown => return Err(stale(store, id, user_id).await),
+    };
+    // The patch is merged into the row as read, so the write is always conditional on that read.
+    // Without If-Match, losing a race just means merging again.
+    for _ in 0..PATCH_ATTEMPTS {
+        let mut config = store
+            .get_newsletter_by_id(id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+        if expected.is_some_and(|t| t != config.updated_at) {
+            return Err(stale(store, id, user_id).await);
+        }
+        let read_at = config.updated_at;
+        store::apply_patch(&mut config, &body);
+        // Only the sections this patch touches; untouched entries were accepted earlier.
+        if let Some(keys) = body.features.as_value().and_then(|p| p.as_object()) {
+            let touched: serde_json::Map<String, serde_json::Value> = keys
+                .keys()
+                .filter_map(|k| config.features.get(k).map(|v| (k.clone(), v.clone())))
+                .collect();
+            sections::check_features(store, user_id, &serde_json::Value::Object(touched)).await?;
+        }
+        if let Some(saved) = store.save_newsletter(&config, Some(read_at)).await? {
+            return TaggedNewsletter::load(store, saved).await;
+        }
+        if expected.is_some() {
+            return Err(stale(store, id, user_id).await);
+        }
+    }
+    Err(stale(store, id, user_id).await)
+}
+
+/// Copies the user's newsletter, `features` and pinned prompt template included, as a new
+/// inactive one titled "<title> (copy)". An optional overlay (same shape as PUT) is applied to
+/// the copy before it is saved, and can rename or activate it.
+#[rocket::post("/me/newsletters/<id>/duplicate", data = "<overlay>")]
+pub async fn duplicate(
+    user: ApprovedUser,
+    store: &State<Store>,
+    quotas: &State<Quotas>,
+    id: &str,
+    overlay: Option<Json<UpdateNewsletterConfig>>,
+) -> Result<TaggedNewsletter, AppError> {
+    let id = AppError::parse_id(id)?;
+    let user_id = user.0.user_id;
+    if let Some(body) = overlay.as_deref() {
+        validation::newsletter_update(body)?;
+        if let Some(f) = body.features.as_ref() {
+            sections::check_features(store, user_id, f).await?;
+        }
+        check_prompt_template_version(store, body.prompt_template_version).await?;
+    }
+    let mut copy = store
+        .get_newsletter_by_id(id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+    check_newsletter_quota(store, quotas, user_id, 1).await?;
+    copy.title = format!("{} (copy)", copy.title.trim())
+        .trim()
+        .chars()
+        .take(validation::MAX_TITLE_LEN)
+        .collect();
+    copy.is_active = false;
+    if let Some(body) = overlay.as_deref() {
+        store::apply_update(&mut copy, body);
+    }
+    let created = store
+        .create_newsletter(user_id, &copy.delivery_email, &copy.to_create_body())
+        .await?;
+    Ok(created.into())
+}
+
+/// Adds the events of an iCalendar file (e.g. an exported holiday calendar) to the newsletter's
+/// blackouts, skipping ones it already has; with `replace=true` they replace the list instead.
+#[rocket::post("/me/newsletters/<id>/blackouts/ical?<replace>", data = "<data>")]
+pub async fn import_blackouts(
+    user: ApprovedUser,
+    store: &State<Store>,
+    id: &str,
+    replace: Option<bool>,
+    data: Data<'_>,
+) -> Result<TaggedNewsletter, AppError> {
+    let id = AppError::parse_id(id)?;
+    let user_id = user.0.user_id;
+    let text = data
+        .open(1.mebibytes())
+        .into_string()
         .await
-        .map_err(|_| Status::InternalServerError)?
-        .ok_or(Status::NotFound)?;
-    Ok(Json(config.into_api_response()))
+        .map_err(|e| AppError::BadRequest(format!("Could not read body: {}", e)))?;
+    if !text.is_complete() {
+        return Err(AppError::BadRequest("Calendar is larger than 1 MiB".into()));
+    }
+    let imported = calendar::parse_ical(&text).map_err(|e| AppError::validation(format!("Invalid iCalendar: {}", e)))?;
+    let mut errors = FieldErrors::default();
+    for (i, b) in imported.iter().enumerate() {
+        validation::check_blackout(&mut errors, &format!("blackouts[{}]", i), b);
+    }
+    errors.into_result()?;
+    for _ in 0..PATCH_ATTEMPTS {
+        let mut config = store
+            .get_newsletter_by_id(id, user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+        let read_at = config.updated_at;
+        if replace.unwrap_or(false) {
+            config.blackouts.clear();
+        }
+        for b in &imported {
+            if !config.blackouts.contains(b) {
+                config.blackouts.push(b.clone());
+            }
+        }
+        if config.blackouts.len() > validation::MAX_BLACKOUTS {
+            return Err(AppError::validation(format!(
+                "A newsletter can have at most {} blackouts; this would make {}",
+                validation::MAX_BLACKOUTS,
+                config.blackouts.len()
+            )));
+        }
+        config.updated_at = Utc::now();
+        if let Some(saved) = store.save_newsletter(&config, Some(read_at)).await? {
+            return TaggedNewsletter::load(store, saved).await;
+        }
+    }
+    Err(stale(store, id, user_id).await)
 }
 
 #[rocket::delete("/me/newsletters/<id>")]
-pub async fn delete(user: ApprovedUser, supabase: &State<SupabaseClient>, id: &str) -> Result<Status, Status> {
-    let id = Uuid::parse_str(id).map_err(|_| Status::BadRequest)?;
-    let ok = supabase
-        .delete_newsletter(id, user.0.user_id)
-        .await
-        .map_err(|_| Status::InternalServerError)?;
-    if ok {
+pub async fn delete(user: ApprovedUser, store: &State<Store>, id: &str) -> Result<Status, AppError> {
+    let id = AppError::parse_id(id)?;
+    if store.delete_newsletter(id, user.0.user_id).await? {
         Ok(Status::NoContent)
     } else {
-        Err(Status::NotFound)
+        Err(AppError::NotFound("Newsletter not found".into()))
     }
 }
 
-#[rocket::options("/me/newsletters/<_id>/send-sample")]
-pub fn send_sample_options(_id: &str) -> Status {
-    Status::NoContent
-}
-
-#[rocket::post("/me/newsletters/<id>/send-sample", data = "<overlay>")]
-pub async fn send_sample(
-    user: ApprovedUser,
-    supabase: &State<SupabaseClient>,
-    openclaw: &State<OpenClawConfig>,
-    email_config: &State<EmailConfig>,
-    client: &State<reqwest::Client>,
+/// Loads the user's newsletter and applies an optional unsaved overlay (as sent by the edit page).
+async fn load_with_overlay(
+    store: &Store,
+    user_id: Uuid,
     id: &str,
-    overlay: Option<Json<UpdateNewsletterConfig>>,
-) -> Result<Json<serde_json::Value>, (Status, String)> {
-    eprintln!("[send-sample] POST id={}", id);
-    let id = Uuid::parse_str(id).map_err(|_| (Status::BadRequest, "Invalid newsletter id".into()))?;
-    let mut config = supabase
-        .get_newsletter_by_id(id, user.0.user_id)
+    overlay: Option<&UpdateNewsletterConfig>,
+    log_tag: &str,
+) -> Result<NewsletterConfig, AppError> {
+    let id = AppError::parse_id(id)?;
+    if let Some(body) = overlay {
+        validation::newsletter_update(body)?;
+    }
+    let mut config = store
+        .get_newsletter_by_id(id, user_id)
         .await
         .map_err(|e| {
-            eprintln!("[send-sample] get_newsletter_by_id failed: {}", e);
-            (Status::InternalServerError, e)
+            eprintln!("[{}] get_newsletter_by_id failed: {}", log_tag, e);
+            e
         })?
-        .ok_or((Status::NotFound, "Newsletter not found".into()))?;
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
 
-    if let Some(ref body) = overlay {
+    if let Some(body) = overlay {
         if let Some(t) = body.title.as_ref() {
             config.title = t.clone();
         }
@@ -134,39 +529,289 @@ pub async fn send_sample(
             config.delivery_email = e.clone();
         }
         if let Some(f) = body.features.as_ref() {
+            sections::check_features(store, user_id, f).await?;
             config.features = f.clone();
         }
+        if let Some(v) = body.prompt_template_version {
+            config.prompt_template_version = Some(v);
+        }
     }
+    Ok(config)
+}
 
-    let body = openclaw_client::generate_newsletter(client.inner(), openclaw.inner(), &config)
+/// Generates the newsletter body for `config`; empty output is treated as an error.
+async fn generate_body(
+    store: &Store,
+    client: &reqwest::Client,
+    openclaw: &OpenClawConfig,
+    config: &NewsletterConfig,
+    log_tag: &str,
+) -> Result<String, AppError> {
+    let prompt = prompts::build_for(store, config)
+        .await
+        .map_err(|e| {
+            eprintln!("[{}] build prompt failed: {}", log_tag, e);
+            e
+        })?;
+    let body = openclaw_client::generate_newsletter(client, openclaw, &prompt)
         .await
         .map_err(|e| {
-            eprintln!("[send-sample] generate_newsletter failed: {}", e);
-            (Status::UnprocessableEntity, e)
+            eprintln!("[{}] generate_newsletter failed: {}", log_tag, e);
+            e
         })?;
 
     let body = body.trim();
     if body.is_empty() {
-        eprintln!("[send-sample] OpenClaw returned empty content");
-        return Err((
-            Status::UnprocessableEntity,
+        eprintln!("[{}] OpenClaw returned empty content", log_tag);
+        return Err(AppError::Generation(
             "OpenClaw did not return any content. Check the agent and gateway.".into(),
         ));
     }
+    Ok(body.to_string())
+}
 
-    let subject = format!("{} – Sample – {}", config.title, Utc::now().format("%Y-%m-%d %H:%M"));
-    email::send_newsletter(
-        email_config.inner(),
-        &config.delivery_email,
-        &subject,
-        body,
+#[rocket::options("/me/newsletters/<_id>/send-sample")]
+pub fn send_sample_options(_id: &str) -> Status {
+    Status::NoContent
+}
+
+/// A sample counts against both the hourly sample limit and the daily generation limit.
+fn check_sample_quota(quotas: &Quotas, user_id: Uuid) -> Result<(), AppError> {
+    rate_limit::check_all(
+        user_id,
+        &[(&quotas.samples, "Sample"), (&quotas.generations, "Daily generation")],
+    )
+}
+
+/// Takes back what `check_sample_quota` recorded, for a sample that was never queued.
+fn release_sample_quota(quotas: &Quotas, user_id: Uuid) {
+    quotas.samples.release(user_id);
+    quotas.generations.release(user_id);
+}
+
+/// Queues a sample send and returns its job id; poll `GET /api/jobs/<id>` for the outcome.
+#[rocket::post("/me/newsletters/<id>/send-sample", data = "<overlay>")]
+pub async fn send_sample(
+    user: ApprovedUser,
+    store: &State<Store>,
+    queue: &State<JobQueue>,
+    quotas: &State<Quotas>,
+    id: &str,
+    overlay: Option<Json<UpdateNewsletterConfig>>,
+) -> Result<status::Accepted<Json<serde_json::Value>>, AppError> {
+    eprintln!("[send-sample] POST id={}", id);
+    let config = load_with_overlay(
+        store,
+        user.0.user_id,
+        id,
+        overlay.as_deref(),
+        "send-sample",
     )
-    .await
-    .map_err(|e| {
-        eprintln!("[send-sample] send_newsletter failed: {}", e);
-        (Status::InternalServerError, e)
+    .await?;
+    check_sample_quota(quotas, user.0.user_id)?;
+    let job_id = queue.enqueue_sample(config, None).await.map_err(|e| {
+        eprintln!("[send-sample] enqueue failed: {}", e);
+        release_sample_quota(quotas, user.0.user_id);
+        e
     })?;
+    Ok(status::Accepted(Json(serde_json::json!({
+        "job_id": job_id,
+        "status": "queued",
+    }))))
+}
 
-    eprintln!("[send-sample] sent to {}", config.delivery_email);
-    Ok(Json(serde_json::json!({ "sent": true })))
+/// Runs today's issue now through the scheduled path: real subject, run log and delivery.
+/// Meant for redoing a failed issue; 409 if a run is in progress or today's issue already went
+/// out. Poll `GET /api/jobs/<id>` for the outcome.
+#[rocket::post("/me/newsletters/<id>/run")]
+pub async fn run_now(
+    user: ApprovedUser,
+    store: &State<Store>,
+    queue: &State<JobQueue>,
+    quotas: &State<Quotas>,
+    id: &str,
+) -> Result<status::Accepted<Json<serde_json::Value>>, AppError> {
+    let id = AppError::parse_id(id)?;
+    let config = store
+        .get_newsletter_by_id(id, user.0.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Newsletter not found".into()))?;
+    rate_limit::check_all(user.0.user_id, &[(&quotas.generations, "Daily generation")])?;
+    let job_id = queue.enqueue_run_now(config).await.inspect_err(|_| {
+        // Nothing was queued (e.g. 409 for an in-flight or already-sent issue), so nothing is used.
+        quotas.generations.release(user.0.user_id);
+    })?;
+    Ok(status::Accepted(Json(serde_json::json!({
+        "job_id": job_id,
+        "status": "queued",
+    }))))
 }
+
+pub const DEFAULT_SCHEDULE_DAYS: u32 = 7;
+pub const MAX_SCHEDULE_DAYS: u32 = 90;
+
+/// Upcoming sends across all the user's newsletters over the next `days` (default 7), soonest
+/// first. Days the scheduler will skip (paused, blacked out) are left out.
+#[rocket::get("/me/schedule?<days>")]
+pub async fn schedule(
+    user: ApprovedUser,
+    store: &State<Store>,
+    days: Option<u32>,
+) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let days = days.unwrap_or(DEFAULT_SCHEDULE_DAYS).clamp(1, MAX_SCHEDULE_DAYS);
+    let now = Utc::now();
+    let until = now + Duration::days(i64::from(days));
+    let configs: Vec<_> = store::all_newsletters_by_user(store, user.0.user_id)
+        .await?
+        .into_iter()
+        .filter(|c| c.is_active)
+        .collect();
+    let last_runs = last_runs(store, &configs).await?;
+    let mut sends = Vec::new();
+    for config in &configs {
+        let last_run_at = last_runs.get(&config.id).map(|l| l.run_at);
+        for at in scheduler::upcoming_runs(config, last_run_at, now).take_while(|at| *at < until) {
+            sends.push((at, config));
+        }
+    }
+    sends.sort_by(|(a, x), (b, y)| a.cmp(b).then_with(|| x.title.cmp(&y.title)));
+    Ok(Json(
+        sends
+            .into_iter()
+            .map(|(at, config)| {
+                serde_json::json!({
+                    "newsletter_id": config.id,
+                    "title": config.title,
+                    "delivery_email": config.delivery_email,
+                    "run_at": at.to_rfc3339(),
+                })
+            })
+            .collect(),
+    ))
+}
+
+impl SampleProgress {
+    fn into_event(self) -> Event {
+        match self {
+            SampleProgress::Queued { job_id } => {
+                Event::json(&serde_json::json!({ "job_id": job_id })).event("queued")
+            }
+            SampleProgress::Generating => Event::json(&serde_json::json!({})).event("generating"),
+            SampleProgress::Token(text) => Event::json(&serde_json::json!({ "text": text })).event("token"),
+            SampleProgress::Rendering { subject } => {
+                Event::json(&serde_json::json!({ "subject": subject })).event("rendering")
+            }
+            SampleProgress::Sending => Event::json(&serde_json::json!({})).event("sending"),
+            SampleProgress::Done => Event::json(&serde_json::json!({ "sent": true })).event("done"),
+            SampleProgress::Error(message) => {
+                Event::json(&serde_json::json!({ "message": message })).event("error")
+            }
+        }
+    }
+}
+
+#[rocket::options("/me/newsletters/<_id>/send-sample/stream")]
+pub fn send_sample_stream_options(_id: &str) -> Status {
+    Status::NoContent
+}
+
+/// Send-sample as Server-Sent Events: queued, generating, token (as the gateway streams),
+/// rendering, sending, then done or error. The job continues if the client disconnects.
+#[rocket::post("/me/newsletters/<id>/send-sample/stream", data = "<overlay>")]
+pub async fn send_sample_stream(
+    user: ApprovedUser,
+    store: &State<Store>,
+    queue: &State<JobQueue>,
+    quotas: &State<Quotas>,
+    id: &str,
+    overlay: Option<Json<UpdateNewsletterConfig>>,
+) -> Result<EventStream![], AppError> {
+    eprintln!("[send-sample-stream] POST id={}", id);
+    let config = load_with_overlay(
+        store,
+        user.0.user_id,
+        id,
+        overlay.as_deref(),
+        "send-sample-stream",
+    )
+    .await?;
+    check_sample_quota(quotas, user.0.user_id)?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    queue.enqueue_sample(config, Some(tx)).await.map_err(|e| {
+        eprintln!("[send-sample-stream] enqueue failed: {}", e);
+        release_sample_quota(quotas, user.0.user_id);
+        e
+    })?;
+
+    Ok(EventStream! {
+        while let Some(progress) = rx.recv().await {
+            yield progress.into_event();
+        }
+    })
+}
+
+#[rocket::options("/me/newsletters/<_id>/preview")]
+pub fn preview_options(_id: &str) -> Status {
+    Status::NoContent
+}
+
+/// Same overlay and generation as send-sample, but returns the result instead of emailing it.
+#[rocket::post("/me/newsletters/<id>/preview", data = "<overlay>")]
+pub async fn preview(
+    user: ApprovedUser,
+    store: &State<Store>,
+    openclaw: &State<OpenClawConfig>,
+    client: &State<reqwest::Client>,
+    quotas: &State<Quotas>,
+    id: &str,
+    overlay: Option<Json<UpdateNewsletterConfig>>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let config = load_with_overlay(store, user.0.user_id, id, overlay.as_deref(), "preview").await?;
+    rate_limit::check_all(
+        user.0.user_id,
+        &[(&quotas.previews, "Preview"), (&quotas.generations, "Daily generation")],
+    )?;
+    let body = generate_body(store, client.inner(), openclaw.inner(), &config, "preview").await?;
+    Ok(Json(serde_json::json!({
+        "subject": scheduler::issue_subject(&config, Utc::now()),
+        "markdown": body,
+        "html": email::render_html(&body),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rate_limit::RateLimiter;
+    use crate::store::memory::MemoryStore;
+    use std::sync::Arc;
+    use std::time::Duration as StdDuration;
+
+    fn quotas(max_newsletters: u64) -> Quotas {
+        let limiter = || Arc::new(RateLimiter::new(10, StdDuration::from_secs(60)));
+        Quotas {
+            max_newsletters,
+            previews: limiter(),
+            samples: limiter(),
+            generations: limiter(),
+        }
+    }
+
+    #[tokio::test]
+    async fn newsletter_cap_is_a_conflict_not_a_rate_limit() {
+        let store: Store = Arc::new(MemoryStore::new());
+        let user_id = Uuid::new_v4();
+        let body = CreateNewsletterConfig {
+            title: Some("Daily".into()),
+            topics: Some(vec!["rust".into()]),
+            ..CreateNewsletterConfig::default()
+        };
+        store.create_newsletter(user_id, "a@example.com", &body).await.unwrap();
+        let quotas = quotas(2);
+        assert!(check_newsletter_quota(&store, &quotas, user_id, 1).await.is_ok());
+        let err = check_newsletter_quota(&store, &quotas, user_id, 2).await.unwrap_err();
+        assert!(matches!(err, AppError::Conflict(_)), "{:?}", err);
+    }
+}
+
@@ -0,0 +1,132 @@
+//! Preset gallery: starting points for a new newsletter. Admins publish curated presets; any
+//! approved user can publish their own. Every approved user sees every preset, so presets may
+//! only use built-in sections.
+
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use uuid::Uuid;
+
+use crate::auth::{AdminUser, ApprovedUser};
+use crate::error::AppError;
+use crate::models::{CreateNewsletterPreset, UpdateNewsletterConfig};
+use crate::rate_limit::Quotas;
+use crate::routes::newsletters::{check_newsletter_quota, check_prompt_template_version, TaggedNewsletter};
+use crate::routes::sections;
+use crate::store::{self, Store};
+use crate::validation::{self, FieldErrors};
+
+#[rocket::get("/presets")]
+pub async fn list(user: ApprovedUser, store: &State<Store>) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let presets = store.list_presets().await?;
+    Ok(Json(
+        presets
+            .into_iter()
+            .map(|p| p.into_api_response(user.0.user_id))
+            .collect(),
+    ))
+}
+
+async fn publish(
+    store: &Store,
+    user_id: Uuid,
+    curated: bool,
+    body: &CreateNewsletterPreset,
+) -> Result<serde_json::Value, AppError> {
+    validation::preset_create(body)?;
+    if let Some(f) = body.features.as_ref() {
+        let built_in: Vec<_> = store
+            .list_sections_for_user(user_id)
+            .await?
+            .into_iter()
+            .filter(|s| s.user_id.is_none())
+            .collect();
+        let mut errors = FieldErrors::default();
+        sections::validate_features(&mut errors, f, &built_in);
+        errors.into_result()?;
+    }
+    check_prompt_template_version(store, body.prompt_template_version).await?;
+    let preset = store.create_preset(user_id, curated, body).await?;
+    Ok(preset.into_api_response(user_id))
+}
+
+#[rocket::post("/me/presets", data = "<body>")]
+pub async fn create(
+    user: ApprovedUser,
+    store: &State<Store>,
+    body: Json<CreateNewsletterPreset>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    Ok(Json(publish(store, user.0.user_id, false, &body).await?))
+}
+
+/// Deletes a preset the user published; curated presets are left to admins.
+#[rocket::delete("/me/presets/<id>")]
+pub async fn delete(user: ApprovedUser, store: &State<Store>, id: &str) -> Result<Status, AppError> {
+    let id = AppError::parse_id(id)?;
+    if store.delete_preset(id, Some(user.0.user_id)).await? {
+        Ok(Status::NoContent)
+    } else {
+        Err(AppError::NotFound("Preset not found".into()))
+    }
+}
+
+#[rocket::post("/admin/presets", data = "<body>")]
+pub async fn admin_create(
+    admin: AdminUser,
+    store: &State<Store>,
+    body: Json<CreateNewsletterPreset>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    Ok(Json(publish(store, admin.0.user_id, true, &body).await?))
+}
+
+/// Deletes any preset, curated or not (e.g. to moderate the gallery).
+#[rocket::delete("/admin/presets/<id>")]
+pub async fn admin_delete(_admin: AdminUser, store: &State<Store>, id: &str) -> Result<Status, AppError> {
+    let id = AppError::parse_id(id)?;
+    if store.delete_preset(id, None).await? {
+        Ok(Status::NoContent)
+    } else {
+        Err(AppError::NotFound("Preset not found".into()))
+    }
+}
+
+/// Creates a newsletter from a preset: its name becomes the title and the user's email the
+/// delivery address. An optional overlay (same shape as PUT) is applied before saving, e.g. to
+/// set the send time or rename it.
+#[rocket::post("/presets/<id>/instantiate", data = "<overlay>")]
+pub async fn instantiate(
+    user: ApprovedUser,
+    store: &State<Store>,
+    quotas: &State<Quotas>,
+    id: &str,
+    overlay: Option<Json<UpdateNewsletterConfig>>,
+) -> Result<TaggedNewsletter, AppError> {
+    let id = AppError::parse_id(id)?;
+    let user_id = user.0.user_id;
+    if let Some(body) = overlay.as_deref() {
+        validation::newsletter_update(body)?;
+    }
+    check_newsletter_quota(store, quotas, user_id, 1).await?;
+    let preset = store
+        .get_preset(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Preset not found".into()))?;
+    let email = overlay
+        .as_deref()
+        .and_then(|o| o.delivery_email.as_deref())
+        .or(user.0.email.as_deref())
+        .ok_or_else(|| AppError::validation("delivery_email is required"))?;
+    let mut config = store::new_config(user_id, email, &preset.to_create_body());
+    if let Some(body) = overlay.as_deref() {
+        store::apply_update(&mut config, body);
+    }
+    // Presets published before topics were required may have none; hold the result to the
+    // same rules as a newsletter created directly.
+    validation::newsletter_create(&config.to_create_body())?;
+    sections::check_features(store, user_id, &config.features).await?;
+    check_prompt_template_version(store, config.prompt_template_version).await?;
+    let created = store
+        .create_newsletter(user_id, &config.delivery_email, &config.to_create_body())
+        .await?;
+    Ok(created.into())
+}
@@ -0,0 +1,13 @@
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::auth::ApprovedUser;
+use crate::error::AppError;
+use crate::store::Store;
+
+/// All prompt template versions, newest first, so a newsletter can be pinned to one.
+#[rocket::get("/prompt-templates")]
+pub async fn list(_user: ApprovedUser, store: &State<Store>) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let templates = store.list_prompt_templates().await?;
+    Ok(Json(templates.into_iter().map(|t| t.into_api_response()).collect()))
+}
@@ -0,0 +1,109 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use uuid::Uuid;
+
+use crate::auth::ApprovedUser;
+use crate::error::AppError;
+use crate::models::{CreateNewsletterSection, NewsletterSection, UpdateNewsletterSection};
+use crate::store::Store;
+use crate::validation::FieldErrors;
+
+/// Checks a `features` map against the section catalog: every key must be a known section
+/// and every value an object with an optional boolean `enabled` and string `custom_request`.
+/// Problems are collected into `errors` under `features` or `features.<key>`.
+pub fn validate_features(errors: &mut FieldErrors, features: &serde_json::Value, sections: &[NewsletterSection]) {
+    let Some(map) = features.as_object() else {
+        errors.add("features", "must be an object keyed by section");
+        return;
+    };
+    for (key, value) in map {
+        let field = format!("features.{}", key);
+        if !sections.iter().any(|s| &s.key == key) {
+            errors.add(field, "is not a known section");
+            continue;
+        }
+        let Some(obj) = value.as_object() else {
+            errors.add(field, "must be an object");
+            continue;
+        };
+        if obj.get("enabled").is_some_and(|v| !v.is_boolean()) {
+            errors.add(field.clone(), "enabled must be a boolean");
+        }
+        if obj.get("custom_request").is_some_and(|v| !v.is_string()) {
+            errors.add(field, "custom_request must be a string");
+        }
+    }
+}
+
+/// Loads the owner's catalog and validates `features` against it, mapping failures to 422.
+pub async fn check_features(
+    store: &Store,
+    user_id: Uuid,
+    features: &serde_json::Value,
+) -> Result<(), AppError> {
+    let sections = store.list_sections_for_user(user_id).await?;
+    let mut errors = FieldErrors::default();
+    validate_features(&mut errors, features, &sections);
+    errors.into_result()
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.len() <= 64
+        && key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[rocket::get("/sections")]
+pub async fn list(user: ApprovedUser, store: &State<Store>) -> Result<Json<Vec<serde_json::Value>>, AppError> {
+    let sections = store.list_sections_for_user(user.0.user_id).await?;
+    Ok(Json(sections.into_iter().map(|s| s.into_api_response()).collect()))
+}
+
+#[rocket::post("/me/sections", data = "<body>")]
+pub async fn create(
+    user: ApprovedUser,
+    store: &State<Store>,
+    body: Json<CreateNewsletterSection>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if !is_valid_key(&body.key) {
+        return Err(AppError::validation("key must be 1-64 characters of a-z, 0-9 or _"));
+    }
+    if body.name.trim().is_empty() {
+        return Err(AppError::validation("name must not be empty"));
+    }
+    let existing = store.list_sections_for_user(user.0.user_id).await?;
+    if existing.iter().any(|s| s.key == body.key) {
+        return Err(AppError::Conflict(format!("Section key already exists: {}", body.key)));
+    }
+    let section = store.create_section(user.0.user_id, &body).await?;
+    Ok(Json(section.into_api_response()))
+}
+
+#[rocket::put("/me/sections/<id>", data = "<body>")]
+pub async fn update(
+    user: ApprovedUser,
+    store: &State<Store>,
+    id: &str,
+    body: Json<UpdateNewsletterSection>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let id = AppError::parse_id(id)?;
+    if body.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err(AppError::validation("name must not be empty"));
+    }
+    let section = store
+        .update_section(id, user.0.user_id, &body)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Section not found".into()))?;
+    Ok(Json(section.into_api_response()))
+}
+
+#[rocket::delete("/me/sections/<id>")]
+pub async fn delete(user: ApprovedUser, store: &State<Store>, id: &str) -> Result<Status, AppError> {
+    let id = AppError::parse_id(id)?;
+    if store.delete_section(id, user.0.user_id).await? {
+        Ok(Status::NoContent)
+    } else {
+        Err(AppError::NotFound("Section not found".into()))
+    }
+}
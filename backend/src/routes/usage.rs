@@ -0,0 +1,25 @@
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::auth::ApprovedUser;
+use crate::error::AppError;
+use crate::rate_limit::Quotas;
+use crate::store::{self, Store};
+
+/// The user's usage against each quota. Rate limits are sliding windows; `resets_in_seconds` is
+/// when the oldest counted use drops out of the window.
+#[rocket::get("/me/usage")]
+pub async fn get(
+    user: ApprovedUser,
+    store: &State<Store>,
+    quotas: &State<Quotas>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user_id = user.0.user_id;
+    let newsletters = store::count_newsletters_by_user(store, user_id).await?;
+    Ok(Json(serde_json::json!({
+        "newsletters": { "used": newsletters, "limit": quotas.max_newsletters },
+        "samples": quotas.samples.usage(user_id).into_api_response(),
+        "previews": quotas.previews.usage(user_id).into_api_response(),
+        "generations": quotas.generations.usage(user_id).into_api_response(),
+    })))
+}
@@ -1,77 +1,101 @@
-use chrono::{Timelike, Utc};
+use chrono::{DateTime, Utc};
 use reqwest::Client;
 use std::time::Duration;
+use uuid::Uuid;
 
+use crate::calendar;
 use crate::email::{self, EmailConfig};
+use crate::error::AppError;
+use crate::jobs::JobQueue;
 use crate::models::NewsletterConfig;
 use crate::openclaw_client::{self, OpenClawConfig};
-use crate::supabase::SupabaseClient;
+use crate::prompts;
+use crate::rate_limit::Quotas;
+use crate::store::Store;
 
-pub fn run_scheduler(
-    supabase: SupabaseClient,
-    openclaw: OpenClawConfig,
-    email_config: EmailConfig,
-) {
+/// A newsletter is due from its send_time_utc until this many minutes later, once per UTC day.
+const DUE_WINDOW_MINUTES: u32 = 15;
+
+/// How far ahead `upcoming_runs` looks for a day that is not paused or blacked out.
+const LOOKAHEAD_DAYS: usize = 2 * 366;
+
+/// Every five minutes, enqueues a job for each due newsletter; the job workers run them.
+pub fn run_scheduler(store: Store, queue: JobQueue, quotas: Quotas) {
     tokio::spawn(async move {
-        let client = Client::new();
         let check_interval = Duration::from_secs(60 * 5);
         loop {
             tokio::time::sleep(check_interval).await;
-            if let Err(e) = run_tick(&supabase, &client, &openclaw, &email_config).await {
+            if let Err(e) = run_tick(&store, &queue, &quotas).await {
                 eprintln!("scheduler tick error: {}", e);
             }
         }
     });
 }
 
-async fn run_tick(
-    supabase: &SupabaseClient,
-    client: &Client,
-    openclaw: &OpenClawConfig,
-    email_config: &EmailConfig,
-) -> Result<(), String> {
-    let configs = supabase.list_active_newsletter_configs().await?;
+/// Paused or blacked-out newsletters, and those whose owner has used up the day's generations,
+/// get a `skipped` run log instead of a run; like any run log it uses up the day, so each
+/// skipped day is recorded once.
+async fn run_tick(store: &Store, queue: &JobQueue, quotas: &Quotas) -> Result<(), AppError> {
+    let now = Utc::now();
+    let configs = store.list_due_newsletter_configs(now, DUE_WINDOW_MINUTES).await?;
     for config in configs {
-        if !is_due(supabase, &config).await? {
+        let user_id = config.user_id;
+        let reason = calendar::skip_reason(&config, now).or_else(|| {
+            quotas
+                .generations
+                .check(user_id)
+                .err()
+                .map(|_| "daily generation limit reached".to_string())
+        });
+        if let Some(reason) = reason {
+            if let Err(e) = store
+                .insert_run_log(config.id, "skipped", Some(&reason), None, None)
+                .await
+            {
+                eprintln!("scheduler skip log error: {}", e);
+            }
             continue;
         }
-        run_one(supabase, client, openclaw, email_config, &config).await?;
-    }
-    Ok(())
-}
-
-async fn is_due(supabase: &SupabaseClient, config: &NewsletterConfig) -> Result<bool, String> {
-    let last = supabase.get_last_run_at(config.id).await?;
-    let now = Utc::now();
-    let today = now.date_naive();
-    if let Some(last_run) = last {
-        if last_run.date_naive() >= today {
-            return Ok(false);
+        match queue.enqueue_scheduled_run(config).await {
+            Ok(Some(_)) => {}
+            // Not queued, so the generation counted above is not used.
+            Ok(None) => quotas.generations.release(user_id),
+            Err(e) => {
+                quotas.generations.release(user_id);
+                eprintln!("scheduler enqueue error: {}", e);
+            }
         }
     }
-    let send_time = config.send_time_utc;
-    let send_mins = send_time.hour() * 60 + send_time.minute();
-    let now_mins = now.hour() * 60 + now.minute();
-    Ok(now_mins >= send_mins && now_mins < send_mins + 15)
+    Ok(())
 }
 
-async fn run_one(
-    supabase: &SupabaseClient,
+/// Generates and sends one scheduled issue, recording the outcome in newsletter_run_log.
+pub async fn run_one(
+    store: &Store,
     client: &Client,
     openclaw: &OpenClawConfig,
     email_config: &EmailConfig,
     config: &NewsletterConfig,
-) -> Result<(), String> {
-    let body = match openclaw_client::generate_newsletter(client, openclaw, config).await {
+) -> Result<(), AppError> {
+    let prompt = match prompts::build_for(store, config).await {
+        Ok(p) => p,
+        Err(e) => {
+            let _ = store
+                .insert_run_log(config.id, "failure", Some(&e.to_string()), None, config.prompt_template_version)
+                .await;
+            return Err(e);
+        }
+    };
+    let body = match openclaw_client::generate_newsletter(client, openclaw, &prompt).await {
         Ok(b) => b,
         Err(e) => {
-            let _ = supabase
-                .insert_run_log(config.id, "failure", Some(&e), None)
+            let _ = store
+                .insert_run_log(config.id, "failure", Some(&e.to_string()), None, prompt.template_version)
                 .await;
             return Err(e);
         }
     };
-    let subject = format!("{} – {}", config.title, Utc::now().format("%Y-%m-%d"));
+    let subject = issue_subject(config, Utc::now());
     if let Err(e) = email::send_newsletter(
         email_config,
         &config.delivery_email,
@@ -80,13 +104,62 @@ async fn run_one(
     )
     .await
     {
-        let _ = supabase
-            .insert_run_log(config.id, "failure", Some(&e), None)
+        let _ = store
+            .insert_run_log(config.id, "failure", Some(&e.to_string()), None, prompt.template_version)
             .await;
         return Err(e);
     }
-    supabase
-        .insert_run_log(config.id, "success", None, None)
+    store
+        .insert_run_log(config.id, "success", None, None, prompt.template_version)
         .await?;
     Ok(())
 }
+
+/// When the issue for `now`'s slot (its UTC day) was sent successfully, if it was.
+pub async fn slot_sent_at(
+    store: &Store,
+    config_id: Uuid,
+    now: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let last = store.list_run_logs(Some(config_id), Some("success"), 1).await?;
+    Ok(last
+        .into_iter()
+        .map(|r| r.run_at)
+        .find(|at| at.date_naive() == now.date_naive()))
+}
+
+/// When the coming issues will go out, soonest first, by the same rules as `run_tick`: one slot
+/// per UTC day at send_time_utc, skipped while paused or blacked out. Today's slot still counts
+/// until its due window closes, unless a run (`last_run_at`) is already logged today.
+pub fn upcoming_runs(
+    config: &NewsletterConfig,
+    last_run_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> impl Iterator<Item = DateTime<Utc>> + '_ {
+    let today = now.date_naive();
+    let ran_today = last_run_at.is_some_and(|at| at.date_naive() >= today);
+    let window = chrono::Duration::minutes(i64::from(DUE_WINDOW_MINUTES));
+    let days = if config.is_active { LOOKAHEAD_DAYS } else { 0 };
+    today.iter_days().take(days).filter_map(move |day| {
+        let slot = day.and_time(config.send_time_utc).and_utc();
+        if day == today && (ran_today || now >= slot + window) {
+            return None;
+        }
+        calendar::skip_reason(config, slot.max(now)).is_none().then_some(slot)
+    })
+}
+
+/// The next issue's run time; None if the newsletter is inactive or nothing is due in the
+/// lookahead (e.g. paused for years).
+pub fn next_run_at(
+    config: &NewsletterConfig,
+    last_run_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    upcoming_runs(config, last_run_at, now).next()
+}
+
+/// Subject line of a scheduled issue.
+pub fn issue_subject(config: &NewsletterConfig, at: DateTime<Utc>) -> String {
+    format!("{} – {}", config.title, at.format("%Y-%m-%d"))
+}
@@ -0,0 +1,698 @@
+//! `NewsletterStore` held in process memory. Nothing survives a restart; meant for local
+//! development, demos and offline runs of the full API.
+
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+use super::{NewsletterCursor, NewsletterListQuery, NewsletterPage, NewsletterSort, NewsletterStore, SchemaMigration};
+use crate::error::AppError;
+use crate::models::{
+    AccessRequest, ApiToken, ApprovedUserRow, BackgroundJob, CreateNewsletterConfig, CreateNewsletterPreset,
+    CreateNewsletterSection, NewsletterConfig, NewsletterPreset, NewsletterSection, PromptTemplate, RunLog, UpdateNewsletterSection,
+};
+
+/// Built-in section catalog, matching the rows seeded by the add_newsletter_sections migration.
+const BUILTIN_SECTIONS: [(&str, &str, &str, &str, i32); 4] = [
+    (
+        "kpis",
+        "KPIs",
+        "Key performance indicators: metrics, trends, and how they compare to targets or prior period.",
+        "Highlight the most important metrics, their trend and any outliers.",
+        10,
+    ),
+    (
+        "competitor_analysis",
+        "Competitor analysis",
+        "Summary of what competitors are doing and how they compare.",
+        "Summarize notable competitor moves and how they compare.",
+        20,
+    ),
+    (
+        "market_segment_summary",
+        "Market segment summary",
+        "Overview of market segments, size, and how they are changing.",
+        "Give an overview of the main market segments and how they are changing.",
+        30,
+    ),
+    (
+        "identify_risks",
+        "Identify risks",
+        "Risks and uncertainties that could affect your business or market.",
+        "List the main risks and uncertainties, most likely first.",
+        40,
+    ),
+];
+
+#[derive(Default)]
+struct Data {
+    newsletters: Vec<NewsletterConfig>,
+    run_logs: Vec<RunLog>,
+    approved: Vec<ApprovedUserRow>,
+    roles: Vec<(Uuid, String)>,
+    access_requests: Vec<AccessRequest>,
+    sections: Vec<NewsletterSection>,
+    presets: Vec<NewsletterPreset>,
+    prompt_templates: Vec<PromptTemplate>,
+    jobs: Vec<BackgroundJob>,
+    /// Tokens with their hash, which is never handed out.
+    api_tokens: Vec<(ApiToken, String)>,
+}
+
+pub struct MemoryStore {
+    data: Mutex<Data>,
+}
+
+impl MemoryStore {
+    /// Starts empty apart from the built-in sections. With no prompt_template rows the
+    /// built-in prompt wording is used.
+    pub fn new() -> Self {
+        let now = Utc::now();
+        let sections = BUILTIN_SECTIONS
+            .iter()
+            .map(|(key, name, description, instructions, sort_order)| NewsletterSection {
+                id: Uuid::new_v4(),
+                key: key.to_string(),
+                user_id: None,
+                name: name.to_string(),
+                description: description.to_string(),
+                default_instructions: instructions.to_string(),
+                sort_order: *sort_order,
+                created_at: now,
+                updated_at: now,
+            })
+            .collect();
+        Self {
+            data: Mutex::new(Data {
+                sections,
+                ..Data::default()
+            }),
+        }
+    }
+
+    fn data(&self) -> MutexGuard<'_, Data> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The scheduler's due rule, which the SQL backends express as one query: send_time_utc falls
+/// within the `window_minutes` up to `now` (UTC time of day) and nothing has run yet today.
+fn is_due(config: &NewsletterConfig, last_run_at: Option<DateTime<Utc>>, now: DateTime<Utc>, window_minutes: u32) -> bool {
+    if last_run_at.is_some_and(|last| last.date_naive() >= now.date_naive()) {
+        return false;
+    }
+    let send_mins = config.send_time_utc.hour() * 60 + config.send_time_utc.minute();
+    let now_mins = now.hour() * 60 + now.minute();
+    now_mins >= send_mins && now_mins < send_mins + window_minutes
+}
+
+/// The list filters of `NewsletterListQuery`, as the SQL backends apply them.
+fn list_matches(q: &NewsletterListQuery, c: &NewsletterConfig) -> bool {
+    let t = c.send_time_utc;
+    let in_window = match (q.send_from, q.send_to) {
+        (Some(from), Some(to)) if from > to => t >= from || t <= to,
+        (from, to) => from.is_none_or(|f| t >= f) && to.is_none_or(|e| t <= e),
+    };
+    q.is_active.is_none_or(|a| c.is_active == a)
+        && q.tag.as_ref().is_none_or(|tag| c.tags.contains(tag))
+        && in_window
+}
+
+fn list_order(sort: NewsletterSort, a: &NewsletterConfig, b: &NewsletterConfig) -> Ordering {
+    match sort {
+        NewsletterSort::Created => (b.created_at, b.id).cmp(&(a.created_at, a.id)),
+        NewsletterSort::Title => (&a.title, a.id).cmp(&(&b.title, b.id)),
+        NewsletterSort::NextSend => (a.send_time_utc, a.id).cmp(&(b.send_time_utc, b.id)),
+    }
+}
+
+fn is_after(cursor: &NewsletterCursor, c: &NewsletterConfig) -> bool {
+    match cursor {
+        NewsletterCursor::Created(t, id) => (c.created_at, c.id) < (*t, *id),
+        NewsletterCursor::Title(t, id) => (c.title.as_str(), c.id) > (t.as_str(), *id),
+        NewsletterCursor::NextSend(t, id) => (c.send_time_utc, c.id) > (*t, *id),
+    }
+}
+
+fn newest_first(configs: &mut [NewsletterConfig]) {
+    configs.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+}
+
+#[rocket::async_trait]
+impl NewsletterStore for MemoryStore {
+    async fn list_newsletters_by_user(
+        &self,
+        user_id: Uuid,
+        q: &NewsletterListQuery,
+    ) -> Result<NewsletterPage, AppError> {
+        let mut matching: Vec<_> = self
+            .data()
+            .newsletters
+            .iter()
+            .filter(|c| c.user_id == user_id && list_matches(q, c))
+            .cloned()
+            .collect();
+        matching.sort_by(|a, b| list_order(q.sort, a, b));
+        let total = matching.len() as u64;
+        let items = matching
+            .into_iter()
+            .filter(|c| q.after.as_ref().is_none_or(|cursor| is_after(cursor, c)))
+            .take(q.limit as usize)
+            .collect();
+        Ok(NewsletterPage { items, total })
+    }
+
+    async fn get_newsletter_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<NewsletterConfig>, AppError> {
+        Ok(self
+            .data()
+            .newsletters
+            .iter()
+            .find(|c| c.id == id && c.user_id == user_id)
+            .cloned())
+    }
+
+    async fn create_newsletter(
+        &self,
+        user_id: Uuid,
+        delivery_email: &str,
+        body: &CreateNewsletterConfig,
+    ) -> Result<NewsletterConfig, AppError> {
+        let config = super::new_config(user_id, delivery_email, body);
+        self.data().newsletters.push(config.clone());
+        Ok(config)
+    }
+
+    async fn save_newsletter(
+        &self,
+        c: &NewsletterConfig,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<NewsletterConfig>, AppError> {
+        let mut data = self.data();
+        let Some(config) = data
+            .newsletters
+            .iter_mut()
+            .find(|x| x.id == c.id && x.user_id == c.user_id)
+        else {
+            return Ok(None);
+        };
+        if expected_updated_at.is_some_and(|t| t != config.updated_at) {
+            return Ok(None);
+        }
+        *config = c.clone();
+        Ok(Some(config.clone()))
+    }
+
+    async fn delete_newsletter(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let before = data.newsletters.len();
+        data.newsletters.retain(|c| !(c.id == id && c.user_id == user_id));
+        let deleted = data.newsletters.len() < before;
+        if deleted {
+            data.run_logs.retain(|l| l.newsletter_config_id != id);
+        }
+        Ok(deleted)
+    }
+
+    async fn list_due_newsletter_configs(
+        &self,
+        now: DateTime<Utc>,
+        window_minutes: u32,
+    ) -> Result<Vec<NewsletterConfig>, AppError> {
+        let data = self.data();
+        Ok(data
+            .newsletters
+            .iter()
+            .filter(|c| c.is_active)
+            .filter(|c| {
+                let last_run_at = data
+                    .run_logs
+                    .iter()
+                    .filter(|l| l.newsletter_config_id == c.id)
+                    .map(|l| l.run_at)
+                    .max();
+                is_due(c, last_run_at, now, window_minutes)
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn list_all_newsletters(
+        &self,
+        user_id: Option<Uuid>,
+        is_active: Option<bool>,
+    ) -> Result<Vec<NewsletterConfig>, AppError> {
+        let mut out: Vec<_> = self
+            .data()
+            .newsletters
+            .iter()
+            .filter(|c| user_id.is_none_or(|u| c.user_id == u))
+            .filter(|c| is_active.is_none_or(|a| c.is_active == a))
+            .cloned()
+            .collect();
+        newest_first(&mut out);
+        Ok(out)
+    }
+
+    async fn get_newsletter_any(&self, id: Uuid) -> Result<Option<NewsletterConfig>, AppError> {
+        Ok(self.data().newsletters.iter().find(|c| c.id == id).cloned())
+    }
+
+    async fn set_newsletter_active(&self, id: Uuid, is_active: bool) -> Result<Option<NewsletterConfig>, AppError> {
+        let mut data = self.data();
+        let Some(config) = data.newsletters.iter_mut().find(|c| c.id == id) else {
+            return Ok(None);
+        };
+        config.is_active = is_active;
+        config.updated_at = Utc::now();
+        Ok(Some(config.clone()))
+    }
+
+    async fn insert_run_log(
+        &self,
+        newsletter_config_id: Uuid,
+        status: &str,
+        error_message: Option<&str>,
+        openclaw_response_id: Option<&str>,
+        prompt_template_version: Option<i32>,
+    ) -> Result<(), AppError> {
+        self.data().run_logs.push(RunLog {
+            id: Uuid::new_v4(),
+            newsletter_config_id,
+            run_at: Utc::now(),
+            status: status.to_string(),
+            error_message: error_message.map(str::to_string),
+            openclaw_response_id: openclaw_response_id.map(str::to_string),
+            prompt_template_version,
+        });
+        Ok(())
+    }
+
+    async fn list_run_logs(
+        &self,
+        newsletter_config_id: Option<Uuid>,
+        status: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<RunLog>, AppError> {
+        let mut out: Vec<_> = self
+            .data()
+            .run_logs
+            .iter()
+            .filter(|l| newsletter_config_id.is_none_or(|id| l.newsletter_config_id == id))
+            .filter(|l| status.is_none_or(|s| l.status == s))
+            .cloned()
+            .collect();
+        out.sort_by_key(|c| std::cmp::Reverse(c.run_at));
+        out.truncate(limit as usize);
+        Ok(out)
+    }
+
+    async fn latest_run_logs(&self, newsletter_config_ids: &[Uuid]) -> Result<Vec<RunLog>, AppError> {
+        let mut latest: HashMap<Uuid, RunLog> = HashMap::new();
+        for l in self.data().run_logs.iter() {
+            if !newsletter_config_ids.contains(&l.newsletter_config_id) {
+                continue;
+            }
+            match latest.get(&l.newsletter_config_id) {
+                Some(seen) if seen.run_at >= l.run_at => {}
+                _ => {
+                    latest.insert(l.newsletter_config_id, l.clone());
+                }
+            }
+        }
+        Ok(latest.into_values().collect())
+    }
+
+    async fn is_user_approved(&self, user_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.data().approved.iter().any(|u| u.user_id == user_id))
+    }
+
+    async fn approve_user(&self, user_id: Uuid, approved_by: Option<Uuid>) -> Result<(), AppError> {
+        let mut data = self.data();
+        if !data.approved.iter().any(|u| u.user_id == user_id) {
+            data.approved.push(ApprovedUserRow {
+                user_id,
+                approved_at: Utc::now(),
+                approved_by,
+            });
+        }
+        Ok(())
+    }
+
+    async fn revoke_user(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let before = data.approved.len();
+        data.approved.retain(|u| u.user_id != user_id);
+        Ok(data.approved.len() < before)
+    }
+
+    async fn list_approved_users(&self) -> Result<Vec<ApprovedUserRow>, AppError> {
+        let mut out = self.data().approved.clone();
+        out.sort_by_key(|c| std::cmp::Reverse(c.approved_at));
+        Ok(out)
+    }
+
+    async fn user_has_role(&self, user_id: Uuid, role: &str) -> Result<bool, AppError> {
+        Ok(self.data().roles.iter().any(|(u, r)| *u == user_id && r == role))
+    }
+
+    async fn create_access_request(&self, user_id: Uuid, email: &str, message: &str) -> Result<AccessRequest, AppError> {
+        let mut data = self.data();
+        if data
+            .access_requests
+            .iter()
+            .any(|r| r.user_id == user_id && r.status == "pending")
+        {
+            return Err(AppError::Storage(
+                "access_request: user already has a pending request".into(),
+            ));
+        }
+        let request = AccessRequest {
+            id: Uuid::new_v4(),
+            user_id,
+            email: email.to_string(),
+            message: message.to_string(),
+            status: "pending".into(),
+            reviewed_by: None,
+            reviewed_at: None,
+            created_at: Utc::now(),
+        };
+        data.access_requests.push(request.clone());
+        Ok(request)
+    }
+
+    async fn latest_access_request(&self, user_id: Uuid) -> Result<Option<AccessRequest>, AppError> {
+        Ok(self
+            .data()
+            .access_requests
+            .iter()
+            .filter(|r| r.user_id == user_id)
+            .max_by_key(|r| r.created_at)
+            .cloned())
+    }
+
+    async fn get_access_request(&self, id: Uuid) -> Result<Option<AccessRequest>, AppError> {
+        Ok(self.data().access_requests.iter().find(|r| r.id == id).cloned())
+    }
+
+    async fn list_access_requests(&self, status: Option<&str>) -> Result<Vec<AccessRequest>, AppError> {
+        let mut out: Vec<_> = self
+            .data()
+            .access_requests
+            .iter()
+            .filter(|r| status.is_none_or(|s| r.status == s))
+            .cloned()
+            .collect();
+        out.sort_by_key(|r| r.created_at);
+        Ok(out)
+    }
+
+    async fn review_access_request(
+        &self,
+        id: Uuid,
+        status: &str,
+        reviewed_by: Uuid,
+    ) -> Result<Option<AccessRequest>, AppError> {
+        let mut data = self.data();
+        let Some(request) = data
+            .access_requests
+            .iter_mut()
+            .find(|r| r.id == id && r.status == "pending")
+        else {
+            return Ok(None);
+        };
+        request.status = status.to_string();
+        request.reviewed_by = Some(reviewed_by);
+        request.reviewed_at = Some(Utc::now());
+        Ok(Some(request.clone()))
+    }
+
+    async fn list_sections_for_user(&self, user_id: Uuid) -> Result<Vec<NewsletterSection>, AppError> {
+        let mut out: Vec<_> = self
+            .data()
+            .sections
+            .iter()
+            .filter(|s| s.user_id.is_none_or(|u| u == user_id))
+            .cloned()
+            .collect();
+        out.sort_by(|a, b| a.sort_order.cmp(&b.sort_order).then_with(|| a.name.cmp(&b.name)));
+        Ok(out)
+    }
+
+    async fn create_section(&self, user_id: Uuid, body: &CreateNewsletterSection) -> Result<NewsletterSection, AppError> {
+        let now = Utc::now();
+        let section = NewsletterSection {
+            id: Uuid::new_v4(),
+            key: body.key.clone(),
+            user_id: Some(user_id),
+            name: body.name.clone(),
+            description: body.description.clone().unwrap_or_default(),
+            default_instructions: body.default_instructions.clone().unwrap_or_default(),
+            sort_order: body.sort_order.unwrap_or(100),
+            created_at: now,
+            updated_at: now,
+        };
+        self.data().sections.push(section.clone());
+        Ok(section)
+    }
+
+    async fn update_section(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: &UpdateNewsletterSection,
+    ) -> Result<Option<NewsletterSection>, AppError> {
+        let mut data = self.data();
+        let Some(section) = data
+            .sections
+            .iter_mut()
+            .find(|s| s.id == id && s.user_id == Some(user_id))
+        else {
+            return Ok(None);
+        };
+        if let Some(n) = body.name.as_ref() {
+            section.name = n.clone();
+        }
+        if let Some(d) = body.description.as_ref() {
+            section.description = d.clone();
+        }
+        if let Some(i) = body.default_instructions.as_ref() {
+            section.default_instructions = i.clone();
+        }
+        if let Some(o) = body.sort_order {
+            section.sort_order = o;
+        }
+        section.updated_at = Utc::now();
+        Ok(Some(section.clone()))
+    }
+
+    async fn delete_section(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let before = data.sections.len();
+        data.sections.retain(|s| !(s.id == id && s.user_id == Some(user_id)));
+        Ok(data.sections.len() < before)
+    }
+
+    async fn get_prompt_template(&self, version: Option<i32>) -> Result<Option<PromptTemplate>, AppError> {
+        let data = self.data();
+        let found = match version {
+            Some(v) => data.prompt_templates.iter().find(|t| t.version == v),
+            None => data.prompt_templates.iter().max_by_key(|t| t.version),
+        };
+        Ok(found.cloned())
+    }
+
+    async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplate>, AppError> {
+        let mut out = self.data().prompt_templates.clone();
+        out.sort_by_key(|c| std::cmp::Reverse(c.version));
+        Ok(out)
+    }
+
+    async fn list_presets(&self) -> Result<Vec<NewsletterPreset>, AppError> {
+        let mut out = self.data().presets.clone();
+        out.sort_by(|a, b| {
+            b.curated
+                .cmp(&a.curated)
+                .then(b.created_at.cmp(&a.created_at))
+                .then(b.id.cmp(&a.id))
+        });
+        Ok(out)
+    }
+
+    async fn get_preset(&self, id: Uuid) -> Result<Option<NewsletterPreset>, AppError> {
+        Ok(self.data().presets.iter().find(|p| p.id == id).cloned())
+    }
+
+    async fn create_preset(
+        &self,
+        user_id: Uuid,
+        curated: bool,
+        body: &CreateNewsletterPreset,
+    ) -> Result<NewsletterPreset, AppError> {
+        let now = Utc::now();
+        let preset = NewsletterPreset {
+            id: Uuid::new_v4(),
+            user_id: Some(user_id),
+            curated,
+            name: body.name.trim().to_string(),
+            description: body.description.clone().unwrap_or_default(),
+            topics: body.topics.clone().unwrap_or_default(),
+            tone: body.tone.clone().unwrap_or_else(|| "neutral".into()),
+            length: body.length.clone().unwrap_or_else(|| "medium".into()),
+            features: body.features.clone().unwrap_or_else(|| serde_json::json!({})),
+            prompt_template_version: body.prompt_template_version,
+            created_at: now,
+            updated_at: now,
+        };
+        self.data().presets.push(preset.clone());
+        Ok(preset)
+    }
+
+    async fn delete_preset(&self, id: Uuid, user_id: Option<Uuid>) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let before = data.presets.len();
+        data.presets.retain(|p| {
+            !(p.id == id && user_id.is_none_or(|u| p.user_id == Some(u) && !p.curated))
+        });
+        Ok(data.presets.len() < before)
+    }
+
+    async fn insert_job(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        kind: &str,
+        newsletter_config_id: Option<Uuid>,
+        slot: Option<NaiveDate>,
+        _instance_id: &str,
+    ) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let taken = slot.is_some()
+            && data.jobs.iter().any(|j| {
+                j.newsletter_config_id == newsletter_config_id && j.slot == slot && j.status != "failed"
+            });
+        if taken {
+            return Ok(false);
+        }
+        data.jobs.push(BackgroundJob {
+            id,
+            user_id,
+            kind: kind.to_string(),
+            newsletter_config_id,
+            status: "queued".into(),
+            error_message: None,
+            slot,
+            created_at: Utc::now(),
+            started_at: None,
+            finished_at: None,
+        });
+        Ok(true)
+    }
+
+    async fn update_job_status(&self, id: Uuid, status: &str, error_message: Option<&str>) -> Result<(), AppError> {
+        let mut data = self.data();
+        if let Some(job) = data.jobs.iter_mut().find(|j| j.id == id) {
+            job.status = status.to_string();
+            if status == "running" {
+                job.started_at = Some(Utc::now());
+            } else {
+                job.error_message = error_message.map(str::to_string);
+                job.finished_at = Some(Utc::now());
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_job(&self, id: Uuid, user_id: Uuid) -> Result<Option<BackgroundJob>, AppError> {
+        Ok(self
+            .data()
+            .jobs
+            .iter()
+            .find(|j| j.id == id && j.user_id == user_id)
+            .cloned())
+    }
+
+    async fn fail_interrupted_jobs(&self, _instance_id: &str) -> Result<(), AppError> {
+        // Nothing outlives the process, so there is never an interrupted job to fail.
+        Ok(())
+    }
+
+    async fn create_api_token(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiToken, AppError> {
+        let token = ApiToken {
+            id: Uuid::new_v4(),
+            user_id,
+            name: name.to_string(),
+            token_prefix: token_prefix.to_string(),
+            scopes: scopes.to_vec(),
+            expires_at,
+            last_used_at: None,
+            revoked_at: None,
+            created_at: Utc::now(),
+        };
+        self.data().api_tokens.push((token.clone(), token_hash.to_string()));
+        Ok(token)
+    }
+
+    async fn list_api_tokens(&self, user_id: Uuid) -> Result<Vec<ApiToken>, AppError> {
+        let mut out: Vec<_> = self
+            .data()
+            .api_tokens
+            .iter()
+            .map(|(t, _)| t)
+            .filter(|t| t.user_id == user_id)
+            .cloned()
+            .collect();
+        out.sort_by_key(|c| std::cmp::Reverse(c.created_at));
+        Ok(out)
+    }
+
+    async fn find_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, AppError> {
+        Ok(self
+            .data()
+            .api_tokens
+            .iter()
+            .find(|(_, h)| h == token_hash)
+            .map(|(t, _)| t.clone()))
+    }
+
+    async fn touch_api_token(&self, id: Uuid) -> Result<(), AppError> {
+        if let Some((token, _)) = self.data().api_tokens.iter_mut().find(|(t, _)| t.id == id) {
+            token.last_used_at = Some(Utc::now());
+        }
+        Ok(())
+    }
+
+    async fn revoke_api_token(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let mut data = self.data();
+        let Some((token, _)) = data
+            .api_tokens
+            .iter_mut()
+            .find(|(t, _)| t.id == id && t.user_id == user_id && t.revoked_at.is_none())
+        else {
+            return Ok(false);
+        };
+        token.revoked_at = Some(Utc::now());
+        Ok(true)
+    }
+
+    async fn pending_migrations(&self) -> Result<Vec<SchemaMigration>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn apply_migrations(&self) -> Result<Vec<SchemaMigration>, AppError> {
+        Ok(Vec::new())
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,619 @@
+//! Storage behind a single trait so the API can run against Supabase (PostgREST), Postgres
+//! directly, a local SQLite file, or process memory. Routes, guards, jobs and the scheduler only see `Store`.
+
+pub mod memory;
+pub mod postgres;
+pub mod sqlite;
+pub mod supabase;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use sqlx::migrate::Migrator;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::models::{
+    AccessRequest, ApiToken, ApprovedUserRow, BackgroundJob, CreateNewsletterConfig, CreateNewsletterPreset,
+    CreateNewsletterSection, NewsletterConfig, NewsletterPreset, NewsletterSection, Patch, PatchNewsletterConfig, PromptTemplate, RunLog, UpdateNewsletterConfig,
+    UpdateNewsletterSection,
+};
+use crate::validation;
+
+/// Shared handle managed by Rocket and cloned into background tasks.
+pub type Store = Arc<dyn NewsletterStore>;
+
+/// Which backend to open, chosen from `STORAGE_BACKEND` in main.rs.
+pub enum StorageBackend {
+    Supabase { url: String, service_role_key: String },
+    Postgres { url: String, max_connections: u32 },
+    Sqlite { url: String },
+    Memory,
+}
+
+impl StorageBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StorageBackend::Supabase { .. } => "supabase",
+            StorageBackend::Postgres { .. } => "postgres",
+            StorageBackend::Sqlite { .. } => "sqlite",
+            StorageBackend::Memory => "memory",
+        }
+    }
+}
+
+pub async fn open(backend: StorageBackend) -> Result<Store, AppError> {
+    Ok(match backend {
+        StorageBackend::Supabase { url, service_role_key } => {
+            Arc::new(supabase::SupabaseClient::new(url, service_role_key))
+        }
+        StorageBackend::Postgres { url, max_connections } => {
+            Arc::new(postgres::PostgresStore::connect(&url, max_connections).await?)
+        }
+        StorageBackend::Sqlite { url } => Arc::new(sqlite::SqliteStore::connect(&url).await?),
+        StorageBackend::Memory => Arc::new(memory::MemoryStore::new()),
+    })
+}
+
+/// An embedded migration, identified the way sqlx records it in `_sqlx_migrations`.
+#[derive(Debug, Clone)]
+pub struct SchemaMigration {
+    pub version: i64,
+    pub description: String,
+}
+
+/// Startup gate: applies pending migrations when `apply` is set (and the backend can), then
+/// refuses to continue while any remain so the API never runs against an outdated schema.
+pub async fn ensure_schema(store: &Store, apply: bool) -> Result<(), AppError> {
+    let pending = store.pending_migrations().await?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    if apply {
+        for m in store.apply_migrations().await? {
+            eprintln!("[migrate] applied {} {}", m.version, m.description);
+        }
+        return Ok(());
+    }
+    let list: Vec<String> = pending.iter().map(|m| format!("{} {}", m.version, m.description)).collect();
+    Err(AppError::Storage(format!(
+        "database schema is behind; pending migrations: {}. Run `speedo-backend migrate` (or set MIGRATE_ON_STARTUP=true)",
+        list.join(", ")
+    )))
+}
+
+/// Order of a user's newsletter list. Each breaks ties on id so pages never overlap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewsletterSort {
+    /// Newest first (the default).
+    Created,
+    /// Title, A to Z.
+    Title,
+    /// UTC send time of day, earliest first: the order they go out each day.
+    NextSend,
+}
+
+impl NewsletterSort {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "created" => Some(NewsletterSort::Created),
+            "title" => Some(NewsletterSort::Title),
+            "next_send" => Some(NewsletterSort::NextSend),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NewsletterSort::Created => "created",
+            NewsletterSort::Title => "title",
+            NewsletterSort::NextSend => "next_send",
+        }
+    }
+
+    /// Cursor for the page that starts after `config`.
+    pub fn cursor_after(&self, config: &NewsletterConfig) -> NewsletterCursor {
+        match self {
+            NewsletterSort::Created => NewsletterCursor::Created(config.created_at, config.id),
+            NewsletterSort::Title => NewsletterCursor::Title(config.title.clone(), config.id),
+            NewsletterSort::NextSend => NewsletterCursor::NextSend(config.send_time_utc, config.id),
+        }
+    }
+}
+
+/// Sort value and id of the last row on a page; the next page starts strictly after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewsletterCursor {
+    Created(DateTime<Utc>, Uuid),
+    Title(String, Uuid),
+    NextSend(NaiveTime, Uuid),
+}
+
+impl NewsletterCursor {
+    pub fn sort(&self) -> NewsletterSort {
+        match self {
+            NewsletterCursor::Created(..) => NewsletterSort::Created,
+            NewsletterCursor::Title(..) => NewsletterSort::Title,
+            NewsletterCursor::NextSend(..) => NewsletterSort::NextSend,
+        }
+    }
+
+    /// Opaque token safe in a header or query string: hex of `<sort>:<id>:<value>`.
+    pub fn encode(&self) -> String {
+        let (id, value) = match self {
+            NewsletterCursor::Created(t, id) => (id, t.to_rfc3339()),
+            NewsletterCursor::Title(t, id) => (id, t.clone()),
+            NewsletterCursor::NextSend(t, id) => (id, t.format("%H:%M:%S%.f").to_string()),
+        };
+        let raw = format!("{}:{}:{}", self.sort().name(), id, value);
+        raw.bytes().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    pub fn decode(token: &str) -> Option<Self> {
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(token.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let raw = String::from_utf8(bytes).ok()?;
+        let mut parts = raw.splitn(3, ':');
+        let sort = NewsletterSort::parse(parts.next()?)?;
+        let id = Uuid::parse_str(parts.next()?).ok()?;
+        let value = parts.next()?;
+        Some(match sort {
+            NewsletterSort::Created => {
+                NewsletterCursor::Created(DateTime::parse_from_rfc3339(value).ok()?.with_timezone(&Utc), id)
+            }
+            NewsletterSort::Title => NewsletterCursor::Title(value.to_string(), id),
+            NewsletterSort::NextSend => {
+                NewsletterCursor::NextSend(NaiveTime::parse_from_str(value, "%H:%M:%S%.f").ok()?, id)
+            }
+        })
+    }
+}
+
+/// Filters, order and page for `list_newsletters_by_user`. `after` must match `sort`.
+#[derive(Debug, Clone)]
+pub struct NewsletterListQuery {
+    pub sort: NewsletterSort,
+    pub after: Option<NewsletterCursor>,
+    pub limit: u32,
+    pub is_active: Option<bool>,
+    pub tag: Option<String>,
+    /// UTC send-time window, both ends inclusive; wraps past midnight when `send_from` is later.
+    pub send_from: Option<NaiveTime>,
+    pub send_to: Option<NaiveTime>,
+}
+
+/// One page of a list plus how many rows match its filters overall.
+#[derive(Debug, Clone)]
+pub struct NewsletterPage {
+    pub items: Vec<NewsletterConfig>,
+    pub total: u64,
+}
+
+/// Every newsletter of the user, by title, fetched page by page.
+pub async fn all_newsletters_by_user(store: &Store, user_id: Uuid) -> Result<Vec<NewsletterConfig>, AppError> {
+    const PAGE: u32 = 200;
+    let mut query = NewsletterListQuery {
+        sort: NewsletterSort::Title,
+        after: None,
+        limit: PAGE,
+        is_active: None,
+        tag: None,
+        send_from: None,
+        send_to: None,
+    };
+    let mut out = Vec::new();
+    loop {
+        let page = store.list_newsletters_by_user(user_id, &query).await?;
+        let done = page.items.len() < PAGE as usize;
+        query.after = page.items.last().map(|c| query.sort.cursor_after(c));
+        out.extend(page.items);
+        if done {
+            return Ok(out);
+        }
+    }
+}
+
+/// How many newsletters the user has.
+pub async fn count_newsletters_by_user(store: &Store, user_id: Uuid) -> Result<u64, AppError> {
+    let query = NewsletterListQuery {
+        sort: NewsletterSort::Created,
+        after: None,
+        limit: 1,
+        is_active: None,
+        tag: None,
+        send_from: None,
+        send_to: None,
+    };
+    Ok(store.list_newsletters_by_user(user_id, &query).await?.total)
+}
+
+/// Up migrations in `migrator` whose version is not in `applied`, oldest first.
+pub(crate) fn pending_in(migrator: &Migrator, applied: &[i64]) -> Vec<SchemaMigration> {
+    migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration() && !applied.contains(&m.version))
+        .map(|m| SchemaMigration {
+            version: m.version,
+            description: m.description.to_string(),
+        })
+        .collect()
+}
+
+#[rocket::async_trait]
+pub trait NewsletterStore: Send + Sync {
+    // Newsletter configs
+
+    /// Up to `query.limit` of the user's newsletters matching its filters, in its order, starting
+    /// after its cursor. `total` ignores the cursor and limit.
+    async fn list_newsletters_by_user(
+        &self,
+        user_id: Uuid,
+        query: &NewsletterListQuery,
+    ) -> Result<NewsletterPage, AppError>;
+
+    async fn get_newsletter_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<NewsletterConfig>, AppError>;
+
+    async fn create_newsletter(
+        &self,
+        user_id: Uuid,
+        delivery_email: &str,
+        body: &CreateNewsletterConfig,
+    ) -> Result<NewsletterConfig, AppError>;
+
+    /// Applies the supplied fields. Returns None if the user has no such newsletter or, when
+    /// `expected_updated_at` is given, if it has been updated since (the write is skipped).
+    async fn update_newsletter(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: &UpdateNewsletterConfig,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<NewsletterConfig>, AppError> {
+        let Some(mut config) = self.get_newsletter_by_id(id, user_id).await? else {
+            return Ok(None);
+        };
+        if expected_updated_at.is_some_and(|t| t != config.updated_at) {
+            return Ok(None);
+        }
+        apply_update(&mut config, body);
+        self.save_newsletter(&config, expected_updated_at).await
+    }
+
+    /// Writes every editable field of `config` (matched on id and user_id). Returns None if it
+    /// is gone or, when `expected_updated_at` is given, if it has been updated since.
+    async fn save_newsletter(
+        &self,
+        config: &NewsletterConfig,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<NewsletterConfig>, AppError>;
+
+    async fn delete_newsletter(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError>;
+
+    /// Active newsletters whose send_time_utc falls within the `window_minutes` up to `now`
+    /// (UTC time of day) and that have not run yet today (UTC), in a single query.
+    async fn list_due_newsletter_configs(
+        &self,
+        now: DateTime<Utc>,
+        window_minutes: u32,
+    ) -> Result<Vec<NewsletterConfig>, AppError>;
+
+    /// Any user's newsletters (admin use), newest first, optionally filtered.
+    async fn list_all_newsletters(
+        &self,
+        user_id: Option<Uuid>,
+        is_active: Option<bool>,
+    ) -> Result<Vec<NewsletterConfig>, AppError>;
+
+    /// Newsletter by id regardless of owner (admin use).
+    async fn get_newsletter_any(&self, id: Uuid) -> Result<Option<NewsletterConfig>, AppError>;
+
+    /// Sets is_active on any user's newsletter (admin use).
+    async fn set_newsletter_active(&self, id: Uuid, is_active: bool) -> Result<Option<NewsletterConfig>, AppError>;
+
+    // Run logs
+
+    async fn insert_run_log(
+        &self,
+        newsletter_config_id: Uuid,
+        status: &str,
+        error_message: Option<&str>,
+        openclaw_response_id: Option<&str>,
+        prompt_template_version: Option<i32>,
+    ) -> Result<(), AppError>;
+
+    /// Run logs across all users, newest first.
+    async fn list_run_logs(
+        &self,
+        newsletter_config_id: Option<Uuid>,
+        status: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<RunLog>, AppError>;
+
+    /// The latest run log of each of `newsletter_config_ids` that has one, in no particular order.
+    async fn latest_run_logs(&self, newsletter_config_ids: &[Uuid]) -> Result<Vec<RunLog>, AppError>;
+
+    // Approvals, roles and access requests
+
+    /// True if the user is in approved_users.
+    async fn is_user_approved(&self, user_id: Uuid) -> Result<bool, AppError>;
+
+    /// Adds the user to approved_users; approving an already-approved user is a no-op.
+    async fn approve_user(&self, user_id: Uuid, approved_by: Option<Uuid>) -> Result<(), AppError>;
+
+    /// Removes the user from approved_users. Returns false if they were not approved.
+    async fn revoke_user(&self, user_id: Uuid) -> Result<bool, AppError>;
+
+    async fn list_approved_users(&self) -> Result<Vec<ApprovedUserRow>, AppError>;
+
+    /// True if the user has `role` in user_roles.
+    async fn user_has_role(&self, user_id: Uuid, role: &str) -> Result<bool, AppError>;
+
+    async fn create_access_request(&self, user_id: Uuid, email: &str, message: &str) -> Result<AccessRequest, AppError>;
+
+    /// The user's most recent access request, if any.
+    async fn latest_access_request(&self, user_id: Uuid) -> Result<Option<AccessRequest>, AppError>;
+
+    async fn get_access_request(&self, id: Uuid) -> Result<Option<AccessRequest>, AppError>;
+
+    /// Access requests, oldest first, optionally filtered by status.
+    async fn list_access_requests(&self, status: Option<&str>) -> Result<Vec<AccessRequest>, AppError>;
+
+    /// Moves a pending request to `status`. Returns None if it does not exist or is not pending.
+    async fn review_access_request(
+        &self,
+        id: Uuid,
+        status: &str,
+        reviewed_by: Uuid,
+    ) -> Result<Option<AccessRequest>, AppError>;
+
+    // Sections and prompt templates
+
+    /// Built-in sections plus the user's own, in display order.
+    async fn list_sections_for_user(&self, user_id: Uuid) -> Result<Vec<NewsletterSection>, AppError>;
+
+    async fn create_section(&self, user_id: Uuid, body: &CreateNewsletterSection) -> Result<NewsletterSection, AppError>;
+
+    /// Updates a user-defined section; built-in sections are never matched.
+    async fn update_section(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: &UpdateNewsletterSection,
+    ) -> Result<Option<NewsletterSection>, AppError>;
+
+    async fn delete_section(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError>;
+
+    /// The given template version, or the latest when `version` is None.
+    async fn get_prompt_template(&self, version: Option<i32>) -> Result<Option<PromptTemplate>, AppError>;
+
+    /// All versions, newest first.
+    async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplate>, AppError>;
+
+    // Presets
+
+    /// Every preset, curated first, then newest first.
+    async fn list_presets(&self) -> Result<Vec<NewsletterPreset>, AppError>;
+
+    async fn get_preset(&self, id: Uuid) -> Result<Option<NewsletterPreset>, AppError>;
+
+    async fn create_preset(
+        &self,
+        user_id: Uuid,
+        curated: bool,
+        body: &CreateNewsletterPreset,
+    ) -> Result<NewsletterPreset, AppError>;
+
+    /// Deletes a preset. With `user_id`, only a non-curated preset that user published matches;
+    /// with None (admins), any preset does.
+    async fn delete_preset(&self, id: Uuid, user_id: Option<Uuid>) -> Result<bool, AppError>;
+
+    // Background jobs
+
+    /// Queues a job owned by `instance_id`, the backend instance whose workers will run it. A
+    /// scheduled run claims its `slot` in the same insert: false (and nothing queued) if a run of
+    /// that newsletter and day is already queued, running or succeeded, on any instance.
+    async fn insert_job(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        kind: &str,
+        newsletter_config_id: Option<Uuid>,
+        slot: Option<NaiveDate>,
+        instance_id: &str,
+    ) -> Result<bool, AppError>;
+
+    /// Moves a job to `status`, stamping started_at for `running` and finished_at otherwise.
+    async fn update_job_status(&self, id: Uuid, status: &str, error_message: Option<&str>) -> Result<(), AppError>;
+
+    async fn get_job(&self, id: Uuid, user_id: Uuid) -> Result<Option<BackgroundJob>, AppError>;
+
+    /// Jobs of `instance_id` still queued or running belong to its previous process; mark them
+    /// failed. Other instances' jobs may still be running and are left alone.
+    async fn fail_interrupted_jobs(&self, instance_id: &str) -> Result<(), AppError>;
+
+    // API tokens
+
+    async fn create_api_token(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiToken, AppError>;
+
+    /// The user's tokens, newest first, never including the hash.
+    async fn list_api_tokens(&self, user_id: Uuid) -> Result<Vec<ApiToken>, AppError>;
+
+    /// Looks a token up by hash, including revoked and expired ones; the caller decides.
+    async fn find_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, AppError>;
+
+    async fn touch_api_token(&self, id: Uuid) -> Result<(), AppError>;
+
+    /// Marks the user's token revoked. Returns false if no active token matched.
+    async fn revoke_api_token(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError>;
+
+    // Schema
+
+    /// Embedded migrations not yet recorded as applied, oldest first. Empty for backends
+    /// without a schema.
+    async fn pending_migrations(&self) -> Result<Vec<SchemaMigration>, AppError>;
+
+    /// Applies pending migrations and returns them. Errors if the backend cannot run DDL.
+    async fn apply_migrations(&self) -> Result<Vec<SchemaMigration>, AppError>;
+
+    // Health
+
+    /// Cheap round trip to confirm the backend is reachable.
+    async fn health_check(&self) -> bool;
+}
+
+/// A new config with the same defaults the Supabase table applies.
+pub(crate) fn new_config(user_id: Uuid, delivery_email: &str, body: &CreateNewsletterConfig) -> NewsletterConfig {
+    let now = Utc::now();
+    NewsletterConfig {
+        id: Uuid::new_v4(),
+        user_id,
+        title: body.title.clone().unwrap_or_default(),
+        topics: body.topics.clone().unwrap_or_default(),
+        tags: body.tags.clone().unwrap_or_default(),
+        tone: body.tone.clone().unwrap_or_else(|| "neutral".into()),
+        length: body.length.clone().unwrap_or_else(|| "medium".into()),
+        send_time_utc: body
+            .send_time_utc
+            .as_deref()
+            .and_then(validation::parse_send_time)
+            .unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+        timezone: body.timezone.clone().unwrap_or_else(|| "UTC".into()),
+        delivery_email: delivery_email.to_string(),
+        is_active: body.is_active.unwrap_or(true),
+        paused_until: body.paused_until.as_deref().and_then(validation::parse_paused_until),
+        blackouts: body.blackouts.clone().unwrap_or_default(),
+        features: body.features.clone().unwrap_or(serde_json::json!({})),
+        prompt_template_version: body.prompt_template_version,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Copies the supplied fields of an update body onto `config` and bumps updated_at.
+pub(crate) fn apply_update(config: &mut NewsletterConfig, body: &UpdateNewsletterConfig) {
+    if let Some(t) = body.title.as_ref() {
+        config.title = t.clone();
+    }
+    if let Some(t) = body.topics.as_ref() {
+        config.topics = t.clone();
+    }
+    if let Some(t) = body.tags.as_ref() {
+        config.tags = t.clone();
+    }
+    if let Some(t) = body.tone.as_ref() {
+        config.tone = t.clone();
+    }
+    if let Some(l) = body.length.as_ref() {
+        config.length = l.clone();
+    }
+    if let Some(t) = body.send_time_utc.as_deref().and_then(validation::parse_send_time) {
+        config.send_time_utc = t;
+    }
+    if let Some(z) = body.timezone.as_ref() {
+        config.timezone = z.clone();
+    }
+    if let Some(e) = body.delivery_email.as_ref() {
+        config.delivery_email = e.clone();
+    }
+    if let Some(a) = body.is_active {
+        config.is_active = a;
+    }
+    if let Some(t) = body.paused_until.as_deref().and_then(validation::parse_paused_until) {
+        config.paused_until = Some(t);
+    }
+    if let Some(b) = body.blackouts.as_ref() {
+        config.blackouts = b.clone();
+    }
+    if let Some(f) = body.features.as_ref() {
+        config.features = f.clone();
+    }
+    if let Some(v) = body.prompt_template_version {
+        config.prompt_template_version = Some(v);
+    }
+    config.updated_at = Utc::now();
+}
+
+/// Applies a PATCH body onto `config` and bumps updated_at. `null` resets a field to what a new
+/// newsletter gets; delivery_email has no default, so validation rejects clearing it.
+pub(crate) fn apply_patch(config: &mut NewsletterConfig, body: &PatchNewsletterConfig) {
+    let defaults = new_config(config.user_id, &config.delivery_email, &CreateNewsletterConfig::default());
+    patch_field(&mut config.title, &body.title, defaults.title);
+    patch_field(&mut config.topics, &body.topics, defaults.topics);
+    patch_field(&mut config.tags, &body.tags, defaults.tags);
+    patch_field(&mut config.tone, &body.tone, defaults.tone);
+    patch_field(&mut config.length, &body.length, defaults.length);
+    match &body.send_time_utc {
+        Patch::Missing => {}
+        Patch::Null => config.send_time_utc = defaults.send_time_utc,
+        Patch::Value(s) => {
+            if let Some(t) = validation::parse_send_time(s) {
+                config.send_time_utc = t;
+            }
+        }
+    }
+    patch_field(&mut config.timezone, &body.timezone, defaults.timezone);
+    if let Patch::Value(e) = &body.delivery_email {
+        config.delivery_email = e.clone();
+    }
+    patch_field(&mut config.is_active, &body.is_active, defaults.is_active);
+    match &body.paused_until {
+        Patch::Missing => {}
+        Patch::Null => config.paused_until = None,
+        Patch::Value(s) => {
+            if let Some(t) = validation::parse_paused_until(s) {
+                config.paused_until = Some(t);
+            }
+        }
+    }
+    patch_field(&mut config.blackouts, &body.blackouts, defaults.blackouts);
+    match &body.features {
+        Patch::Missing => {}
+        Patch::Null => config.features = defaults.features,
+        Patch::Value(p) => merge_patch(&mut config.features, p),
+    }
+    match body.prompt_template_version {
+        Patch::Missing => {}
+        Patch::Null => config.prompt_template_version = None,
+        Patch::Value(v) => config.prompt_template_version = Some(v),
+    }
+    config.updated_at = Utc::now();
+}
+
+fn patch_field<T: Clone>(field: &mut T, patch: &Patch<T>, default: T) {
+    match patch {
+        Patch::Missing => {}
+        Patch::Null => *field = default,
+        Patch::Value(v) => *field = v.clone(),
+    }
+}
+
+/// RFC 7396 JSON Merge Patch: objects merge key by key, `null` removes a key, anything else
+/// replaces the target.
+pub(crate) fn merge_patch(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    let Some(patch) = patch.as_object() else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = serde_json::json!({});
+    }
+    let Some(map) = target.as_object_mut() else {
+        return;
+    };
+    for (key, value) in patch {
+        if value.is_null() {
+            map.remove(key);
+        } else {
+            merge_patch(map.entry(key.clone()).or_insert(serde_json::Value::Null), value);
+        }
+    }
+}
@@ -0,0 +1,867 @@
+//! `NewsletterStore` on a local SQLite file (or `sqlite::memory:`), for self-hosting on a
+//! single box without Supabase. Its migrations live in migrations/sqlite/ and are embedded.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Timelike, Utc};
+use sqlx::migrate::{Migrate, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::types::Json;
+use sqlx::Row;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use super::{NewsletterCursor, NewsletterListQuery, NewsletterPage, NewsletterSort, NewsletterStore, SchemaMigration};
+use crate::error::AppError;
+use crate::models::{
+    AccessRequest, ApiToken, ApprovedUserRow, BackgroundJob, Blackout, CreateNewsletterConfig, CreateNewsletterPreset,
+    CreateNewsletterSection, NewsletterConfig, NewsletterPreset, NewsletterSection, PromptTemplate, RunLog, UpdateNewsletterSection,
+};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// Same filters as the Postgres backend over ?1..?5 (user, active, tag, send_from, send_to);
+/// tags is a JSON array and send_time_utc `HH:MM:SS` text, which orders like the time.
+const NEWSLETTER_LIST_FILTER: &str = "user_id = ?1 and (?2 is null or is_active = ?2) \
+     and (?3 is null or exists (select 1 from json_each(tags) where value = ?3)) \
+     and ((?4 is null or send_time_utc >= ?4) and (?5 is null or send_time_utc <= ?5) \
+     or coalesce(?4 > ?5, 0) and (send_time_utc >= ?4 or send_time_utc <= ?5))";
+
+/// api_token columns safe to return (everything but token_hash).
+const API_TOKEN_COLUMNS: &str =
+    "id, user_id, name, token_prefix, scopes, expires_at, last_used_at, revoked_at, created_at";
+
+pub struct SqliteStore {
+    pool: SqlitePool,
+}
+
+impl SqliteStore {
+    /// Opens (creating if needed) the database at `url`, e.g. `sqlite://speedo.db`.
+    pub async fn connect(url: &str) -> Result<Self, AppError> {
+        let options = SqliteConnectOptions::from_str(url)
+            .map_err(AppError::storage)?
+            .create_if_missing(true)
+            .foreign_keys(true);
+        // An in-memory database exists per connection, so keep exactly one.
+        let max_connections = if url.contains(":memory:") { 1 } else { 5 };
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .connect_with(options)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(Self { pool })
+    }
+}
+
+fn all<T>(rows: Vec<SqliteRow>, f: fn(&SqliteRow) -> Result<T, sqlx::Error>) -> Result<Vec<T>, AppError> {
+    rows.iter().map(f).collect::<Result<_, _>>().map_err(AppError::storage)
+}
+
+fn one<T>(row: Option<SqliteRow>, f: fn(&SqliteRow) -> Result<T, sqlx::Error>) -> Result<Option<T>, AppError> {
+    row.as_ref().map(f).transpose().map_err(AppError::storage)
+}
+
+fn config_from_row(r: &SqliteRow) -> Result<NewsletterConfig, sqlx::Error> {
+    Ok(NewsletterConfig {
+        id: r.try_get("id")?,
+        user_id: r.try_get("user_id")?,
+        title: r.try_get("title")?,
+        topics: r.try_get::<Json<Vec<String>>, _>("topics")?.0,
+        tags: r.try_get::<Json<Vec<String>>, _>("tags")?.0,
+        tone: r.try_get("tone")?,
+        length: r.try_get("length")?,
+        send_time_utc: r.try_get("send_time_utc")?,
+        timezone: r.try_get("timezone")?,
+        delivery_email: r.try_get("delivery_email")?,
+        is_active: r.try_get("is_active")?,
+        paused_until: r.try_get("paused_until")?,
+        blackouts: r.try_get::<Json<Vec<Blackout>>, _>("blackouts")?.0,
+        features: r.try_get::<Json<serde_json::Value>, _>("features")?.0,
+        prompt_template_version: r.try_get("prompt_template_version")?,
+        created_at: r.try_get("created_at")?,
+        updated_at: r.try_get("updated_at")?,
+    })
+}
+
+fn run_log_from_row(r: &SqliteRow) -> Result<RunLog, sqlx::Error> {
+    Ok(RunLog {
+        id: r.try_get("id")?,
+        newsletter_config_id: r.try_get("newsletter_config_id")?,
+        run_at: r.try_get("run_at")?,
+        status: r.try_get("status")?,
+        error_message: r.try_get("error_message")?,
+        openclaw_response_id: r.try_get("openclaw_response_id")?,
+        prompt_template_version: r.try_get("prompt_template_version")?,
+    })
+}
+
+fn approved_user_from_row(r: &SqliteRow) -> Result<ApprovedUserRow, sqlx::Error> {
+    Ok(ApprovedUserRow {
+        user_id: r.try_get("user_id")?,
+        approved_at: r.try_get("approved_at")?,
+        approved_by: r.try_get("approved_by")?,
+    })
+}
+
+fn access_request_from_row(r: &SqliteRow) -> Result<AccessRequest, sqlx::Error> {
+    Ok(AccessRequest {
+        id: r.try_get("id")?,
+        user_id: r.try_get("user_id")?,
+        email: r.try_get("email")?,
+        message: r.try_get("message")?,
+        status: r.try_get("status")?,
+        reviewed_by: r.try_get("reviewed_by")?,
+        reviewed_at: r.try_get("reviewed_at")?,
+        created_at: r.try_get("created_at")?,
+    })
+}
+
+fn section_from_row(r: &SqliteRow) -> Result<NewsletterSection, sqlx::Error> {
+    Ok(NewsletterSection {
+        id: r.try_get("id")?,
+        key: r.try_get("key")?,
+        user_id: r.try_get("user_id")?,
+        name: r.try_get("name")?,
+        description: r.try_get("description")?,
+        default_instructions: r.try_get("default_instructions")?,
+        sort_order: r.try_get("sort_order")?,
+        created_at: r.try_get("created_at")?,
+        updated_at: r.try_get("updated_at")?,
+    })
+}
+
+fn preset_from_row(r: &SqliteRow) -> Result<NewsletterPreset, sqlx::Error> {
+    Ok(NewsletterPreset {
+        id: r.try_get("id")?,
+        user_id: r.try_get("user_id")?,
+        curated: r.try_get("curated")?,
+        name: r.try_get("name")?,
+        description: r.try_get("description")?,
+        topics: r.try_get::<Json<Vec<String>>, _>("topics")?.0,
+        tone: r.try_get("tone")?,
+        length: r.try_get("length")?,
+        features: r.try_get::<Json<serde_json::Value>, _>("features")?.0,
+        prompt_template_version: r.try_get("prompt_template_version")?,
+        created_at: r.try_get("created_at")?,
+        updated_at: r.try_get("updated_at")?,
+    })
+}
+
+fn prompt_template_from_row(r: &SqliteRow) -> Result<PromptTemplate, sqlx::Error> {
+    Ok(PromptTemplate {
+        version: r.try_get("version")?,
+        system_instructions: r.try_get("system_instructions")?,
+        header_template: r.try_get("header_template")?,
+        section_template: r.try_get("section_template")?,
+        section_instructions_template: r.try_get("section_instructions_template")?,
+        section_snippets: r.try_get::<Json<serde_json::Value>, _>("section_snippets")?.0,
+        tone_guidance: r.try_get::<Json<serde_json::Value>, _>("tone_guidance")?.0,
+        length_guidance: r.try_get::<Json<serde_json::Value>, _>("length_guidance")?.0,
+        notes: r.try_get("notes")?,
+        created_at: r.try_get("created_at")?,
+    })
+}
+
+fn job_from_row(r: &SqliteRow) -> Result<BackgroundJob, sqlx::Error> {
+    Ok(BackgroundJob {
+        id: r.try_get("id")?,
+        user_id: r.try_get("user_id")?,
+        kind: r.try_get("kind")?,
+        newsletter_config_id: r.try_get("newsletter_config_id")?,
+        status: r.try_get("status")?,
+        error_message: r.try_get("error_message")?,
+        slot: r.try_get("slot")?,
+        created_at: r.try_get("created_at")?,
+        started_at: r.try_get("started_at")?,
+        finished_at: r.try_get("finished_at")?,
+    })
+}
+
+fn api_token_from_row(r: &SqliteRow) -> Result<ApiToken, sqlx::Error> {
+    Ok(ApiToken {
+        id: r.try_get("id")?,
+        user_id: r.try_get("user_id")?,
+        name: r.try_get("name")?,
+        token_prefix: r.try_get("token_prefix")?,
+        scopes: r.try_get::<Json<Vec<String>>, _>("scopes")?.0,
+        expires_at: r.try_get("expires_at")?,
+        last_used_at: r.try_get("last_used_at")?,
+        revoked_at: r.try_get("revoked_at")?,
+        created_at: r.try_get("created_at")?,
+    })
+}
+
+#[rocket::async_trait]
+impl NewsletterStore for SqliteStore {
+    async fn list_newsletters_by_user(
+        &self,
+        user_id: Uuid,
+        q: &NewsletterListQuery,
+    ) -> Result<NewsletterPage, AppError> {
+        let (order, after) = match q.sort {
+            NewsletterSort::Created => ("created_at desc, id desc", "(created_at, id) < (?7, ?8)"),
+            NewsletterSort::Title => ("title, id", "(title, id) > (?7, ?8)"),
+            NewsletterSort::NextSend => ("send_time_utc, id", "(send_time_utc, id) > (?7, ?8)"),
+        };
+        let mut sql = format!("select * from newsletter_config where {}", NEWSLETTER_LIST_FILTER);
+        if q.after.is_some() {
+            sql.push_str(&format!(" and {}", after));
+        }
+        sql.push_str(&format!(" order by {} limit ?6", order));
+        let query = sqlx::query(&sql)
+            .bind(user_id)
+            .bind(q.is_active)
+            .bind(q.tag.as_deref())
+            .bind(q.send_from)
+            .bind(q.send_to)
+            .bind(i64::from(q.limit));
+        let query = match &q.after {
+            Some(NewsletterCursor::Created(t, id)) => query.bind(*t).bind(*id),
+            Some(NewsletterCursor::Title(t, id)) => query.bind(t.as_str()).bind(*id),
+            Some(NewsletterCursor::NextSend(t, id)) => query.bind(*t).bind(*id),
+            None => query,
+        };
+        let rows = query.fetch_all(&self.pool).await.map_err(AppError::storage)?;
+        let total: i64 = sqlx::query_scalar(&format!(
+            "select count(*) from newsletter_config where {}",
+            NEWSLETTER_LIST_FILTER
+        ))
+        .bind(user_id)
+        .bind(q.is_active)
+        .bind(q.tag.as_deref())
+        .bind(q.send_from)
+        .bind(q.send_to)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok(NewsletterPage {
+            items: all(rows, config_from_row)?,
+            total: total as u64,
+        })
+    }
+
+    async fn get_newsletter_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Option<NewsletterConfig>, AppError> {
+        let row = sqlx::query("select * from newsletter_config where id = ? and user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, config_from_row)
+    }
+
+    async fn create_newsletter(
+        &self,
+        user_id: Uuid,
+        delivery_email: &str,
+        body: &CreateNewsletterConfig,
+    ) -> Result<NewsletterConfig, AppError> {
+        let c = super::new_config(user_id, delivery_email, body);
+        sqlx::query(
+            "insert into newsletter_config (id, user_id, title, topics, tags, tone, length, send_time_utc, timezone, \
+             delivery_email, is_active, paused_until, blackouts, features, prompt_template_version, created_at, \
+             updated_at) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(c.id)
+        .bind(c.user_id)
+        .bind(&c.title)
+        .bind(Json(&c.topics))
+        .bind(Json(&c.tags))
+        .bind(&c.tone)
+        .bind(&c.length)
+        .bind(c.send_time_utc)
+        .bind(&c.timezone)
+        .bind(&c.delivery_email)
+        .bind(c.is_active)
+        .bind(c.paused_until)
+        .bind(Json(&c.blackouts))
+        .bind(Json(&c.features))
+        .bind(c.prompt_template_version)
+        .bind(c.created_at)
+        .bind(c.updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok(c)
+    }
+
+    async fn save_newsletter(
+        &self,
+        c: &NewsletterConfig,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<NewsletterConfig>, AppError> {
+        let res = sqlx::query(
+            "update newsletter_config set title = ?, topics = ?, tags = ?, tone = ?, length = ?, send_time_utc = ?, \
+             timezone = ?, delivery_email = ?, is_active = ?, paused_until = ?, blackouts = ?, features = ?, \
+             prompt_template_version = ?, updated_at = ? where id = ? and user_id = ? and (? is null or updated_at = ?)",
+        )
+        .bind(&c.title)
+        .bind(Json(&c.topics))
+        .bind(Json(&c.tags))
+        .bind(&c.tone)
+        .bind(&c.length)
+        .bind(c.send_time_utc)
+        .bind(&c.timezone)
+        .bind(&c.delivery_email)
+        .bind(c.is_active)
+        .bind(c.paused_until)
+        .bind(Json(&c.blackouts))
+        .bind(Json(&c.features))
+        .bind(c.prompt_template_version)
+        .bind(c.updated_at)
+        .bind(c.id)
+        .bind(c.user_id)
+        .bind(expected_updated_at)
+        .bind(expected_updated_at)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok((res.rows_affected() > 0).then(|| c.clone()))
+    }
+
+    async fn delete_newsletter(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let res = sqlx::query("delete from newsletter_config where id = ? and user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Mirrors `due_newsletter_configs` in the Postgres migrations; send_time_utc is stored as
+    /// `HH:MM:SS` and run_at as RFC 3339 UTC, so both compare as text.
+    async fn list_due_newsletter_configs(
+        &self,
+        now: DateTime<Utc>,
+        window_minutes: u32,
+    ) -> Result<Vec<NewsletterConfig>, AppError> {
+        let now_mins = i64::from(now.hour() * 60 + now.minute());
+        let day_start = now.date_naive().and_time(NaiveTime::MIN).and_utc();
+        let rows = sqlx::query(
+            "select c.* from newsletter_config c where c.is_active = 1 \
+             and cast(substr(c.send_time_utc, 1, 2) as integer) * 60 + cast(substr(c.send_time_utc, 4, 2) as integer) \
+             between ?1 - ?2 + 1 and ?1 \
+             and not exists (select 1 from newsletter_run_log l where l.newsletter_config_id = c.id and l.run_at >= ?3)",
+        )
+        .bind(now_mins)
+        .bind(i64::from(window_minutes))
+        .bind(day_start)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        all(rows, config_from_row)
+    }
+
+    async fn list_all_newsletters(
+        &self,
+        user_id: Option<Uuid>,
+        is_active: Option<bool>,
+    ) -> Result<Vec<NewsletterConfig>, AppError> {
+        let rows = sqlx::query(
+            "select * from newsletter_config where (?1 is null or user_id = ?1) \
+             and (?2 is null or is_active = ?2) order by created_at desc",
+        )
+        .bind(user_id)
+        .bind(is_active)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        all(rows, config_from_row)
+    }
+
+    async fn get_newsletter_any(&self, id: Uuid) -> Result<Option<NewsletterConfig>, AppError> {
+        let row = sqlx::query("select * from newsletter_config where id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, config_from_row)
+    }
+
+    async fn set_newsletter_active(&self, id: Uuid, is_active: bool) -> Result<Option<NewsletterConfig>, AppError> {
+        let row = sqlx::query("update newsletter_config set is_active = ?, updated_at = ? where id = ? returning *")
+            .bind(is_active)
+            .bind(Utc::now())
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, config_from_row)
+    }
+
+    async fn insert_run_log(
+        &self,
+        newsletter_config_id: Uuid,
+        status: &str,
+        error_message: Option<&str>,
+        openclaw_response_id: Option<&str>,
+        prompt_template_version: Option<i32>,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "insert into newsletter_run_log (id, newsletter_config_id, run_at, status, error_message, \
+             openclaw_response_id, prompt_template_version) values (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4())
+        .bind(newsletter_config_id)
+        .bind(Utc::now())
+        .bind(status)
+        .bind(error_message)
+        .bind(openclaw_response_id)
+        .bind(prompt_template_version)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok(())
+    }
+
+    async fn list_run_logs(
+        &self,
+        newsletter_config_id: Option<Uuid>,
+        status: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<RunLog>, AppError> {
+        let rows = sqlx::query(
+            "select * from newsletter_run_log where (?1 is null or newsletter_config_id = ?1) \
+             and (?2 is null or status = ?2) order by run_at desc limit ?3",
+        )
+        .bind(newsletter_config_id)
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        all(rows, run_log_from_row)
+    }
+
+    async fn latest_run_logs(&self, newsletter_config_ids: &[Uuid]) -> Result<Vec<RunLog>, AppError> {
+        if newsletter_config_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!(
+            "select * from (select *, row_number() over (partition by newsletter_config_id order by run_at desc) as n \
+             from newsletter_run_log where newsletter_config_id in ({})) where n = 1",
+            vec!["?"; newsletter_config_ids.len()].join(", ")
+        );
+        let mut query = sqlx::query(&sql);
+        for id in newsletter_config_ids {
+            query = query.bind(*id);
+        }
+        let rows = query.fetch_all(&self.pool).await.map_err(AppError::storage)?;
+        all(rows, run_log_from_row)
+    }
+
+    async fn is_user_approved(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let row = sqlx::query("select 1 from approved_users where user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(row.is_some())
+    }
+
+    async fn approve_user(&self, user_id: Uuid, approved_by: Option<Uuid>) -> Result<(), AppError> {
+        sqlx::query(
+            "insert into approved_users (user_id, approved_at, approved_by) values (?, ?, ?) \
+             on conflict (user_id) do nothing",
+        )
+        .bind(user_id)
+        .bind(Utc::now())
+        .bind(approved_by)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok(())
+    }
+
+    async fn revoke_user(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let res = sqlx::query("delete from approved_users where user_id = ?")
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn list_approved_users(&self) -> Result<Vec<ApprovedUserRow>, AppError> {
+        let rows = sqlx::query("select * from approved_users order by approved_at desc")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        all(rows, approved_user_from_row)
+    }
+
+    async fn user_has_role(&self, user_id: Uuid, role: &str) -> Result<bool, AppError> {
+        let row = sqlx::query("select 1 from user_roles where user_id = ? and role = ?")
+            .bind(user_id)
+            .bind(role)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(row.is_some())
+    }
+
+    async fn create_access_request(&self, user_id: Uuid, email: &str, message: &str) -> Result<AccessRequest, AppError> {
+        let row = sqlx::query(
+            "insert into access_request (id, user_id, email, message, created_at) values (?, ?, ?, ?, ?) returning *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(email)
+        .bind(message)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        access_request_from_row(&row).map_err(AppError::storage)
+    }
+
+    async fn latest_access_request(&self, user_id: Uuid) -> Result<Option<AccessRequest>, AppError> {
+        let row = sqlx::query("select * from access_request where user_id = ? order by created_at desc limit 1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, access_request_from_row)
+    }
+
+    async fn get_access_request(&self, id: Uuid) -> Result<Option<AccessRequest>, AppError> {
+        let row = sqlx::query("select * from access_request where id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, access_request_from_row)
+    }
+
+    async fn list_access_requests(&self, status: Option<&str>) -> Result<Vec<AccessRequest>, AppError> {
+        let rows = sqlx::query("select * from access_request where (?1 is null or status = ?1) order by created_at asc")
+            .bind(status)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        all(rows, access_request_from_row)
+    }
+
+    async fn review_access_request(
+        &self,
+        id: Uuid,
+        status: &str,
+        reviewed_by: Uuid,
+    ) -> Result<Option<AccessRequest>, AppError> {
+        let row = sqlx::query(
+            "update access_request set status = ?, reviewed_by = ?, reviewed_at = ? \
+             where id = ? and status = 'pending' returning *",
+        )
+        .bind(status)
+        .bind(reviewed_by)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        one(row, access_request_from_row)
+    }
+
+    async fn list_sections_for_user(&self, user_id: Uuid) -> Result<Vec<NewsletterSection>, AppError> {
+        let rows = sqlx::query(
+            "select * from newsletter_section where user_id is null or user_id = ? order by sort_order asc, name asc",
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        all(rows, section_from_row)
+    }
+
+    async fn create_section(&self, user_id: Uuid, body: &CreateNewsletterSection) -> Result<NewsletterSection, AppError> {
+        let now = Utc::now();
+        let row = sqlx::query(
+            "insert into newsletter_section (id, key, user_id, name, description, default_instructions, sort_order, \
+             created_at, updated_at) values (?, ?, ?, ?, ?, ?, ?, ?, ?) returning *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(&body.key)
+        .bind(user_id)
+        .bind(&body.name)
+        .bind(body.description.as_deref().unwrap_or(""))
+        .bind(body.default_instructions.as_deref().unwrap_or(""))
+        .bind(body.sort_order.unwrap_or(100))
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        section_from_row(&row).map_err(AppError::storage)
+    }
+
+    async fn update_section(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: &UpdateNewsletterSection,
+    ) -> Result<Option<NewsletterSection>, AppError> {
+        let row = sqlx::query(
+            "update newsletter_section set name = coalesce(?, name), description = coalesce(?, description), \
+             default_instructions = coalesce(?, default_instructions), sort_order = coalesce(?, sort_order), \
+             updated_at = ? where id = ? and user_id = ? returning *",
+        )
+        .bind(body.name.as_deref())
+        .bind(body.description.as_deref())
+        .bind(body.default_instructions.as_deref())
+        .bind(body.sort_order)
+        .bind(Utc::now())
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        one(row, section_from_row)
+    }
+
+    async fn delete_section(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let res = sqlx::query("delete from newsletter_section where id = ? and user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn get_prompt_template(&self, version: Option<i32>) -> Result<Option<PromptTemplate>, AppError> {
+        let query = match version {
+            Some(v) => sqlx::query("select * from prompt_template where version = ?").bind(v),
+            None => sqlx::query("select * from prompt_template order by version desc limit 1"),
+        };
+        let row = query
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, prompt_template_from_row)
+    }
+
+    async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplate>, AppError> {
+        let rows = sqlx::query("select * from prompt_template order by version desc")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        all(rows, prompt_template_from_row)
+    }
+
+    async fn list_presets(&self) -> Result<Vec<NewsletterPreset>, AppError> {
+        let rows = sqlx::query("select * from newsletter_preset order by curated desc, created_at desc, id desc")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        all(rows, preset_from_row)
+    }
+
+    async fn get_preset(&self, id: Uuid) -> Result<Option<NewsletterPreset>, AppError> {
+        let row = sqlx::query("select * from newsletter_preset where id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, preset_from_row)
+    }
+
+    async fn create_preset(
+        &self,
+        user_id: Uuid,
+        curated: bool,
+        body: &CreateNewsletterPreset,
+    ) -> Result<NewsletterPreset, AppError> {
+        let now = Utc::now();
+        let row = sqlx::query(
+            "insert into newsletter_preset (id, user_id, curated, name, description, topics, tone, length, features, \
+             prompt_template_version, created_at, updated_at) values (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) returning *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(user_id)
+        .bind(curated)
+        .bind(body.name.trim())
+        .bind(body.description.as_deref().unwrap_or(""))
+        .bind(Json(body.topics.clone().unwrap_or_default()))
+        .bind(body.tone.as_deref().unwrap_or("neutral"))
+        .bind(body.length.as_deref().unwrap_or("medium"))
+        .bind(Json(body.features.clone().unwrap_or_else(|| serde_json::json!({}))))
+        .bind(body.prompt_template_version)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        preset_from_row(&row).map_err(AppError::storage)
+    }
+
+    async fn delete_preset(&self, id: Uuid, user_id: Option<Uuid>) -> Result<bool, AppError> {
+        let res = sqlx::query("delete from newsletter_preset where id = ?1 and (?2 is null or (user_id = ?2 and not curated))")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn insert_job(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        kind: &str,
+        newsletter_config_id: Option<Uuid>,
+        slot: Option<NaiveDate>,
+        instance_id: &str,
+    ) -> Result<bool, AppError> {
+        let res = sqlx::query(
+            "insert into background_job (id, user_id, kind, newsletter_config_id, status, slot, created_at, instance_id) \
+             values (?, ?, ?, ?, 'queued', ?, ?, ?) on conflict do nothing",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(kind)
+        .bind(newsletter_config_id)
+        .bind(slot)
+        .bind(Utc::now())
+        .bind(instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn update_job_status(&self, id: Uuid, status: &str, error_message: Option<&str>) -> Result<(), AppError> {
+        let query = if status == "running" {
+            sqlx::query("update background_job set status = ?, started_at = ? where id = ?")
+                .bind(status)
+                .bind(Utc::now())
+                .bind(id)
+        } else {
+            sqlx::query("update background_job set status = ?, error_message = ?, finished_at = ? where id = ?")
+                .bind(status)
+                .bind(error_message)
+                .bind(Utc::now())
+                .bind(id)
+        };
+        query.execute(&self.pool).await.map_err(AppError::storage)?;
+        Ok(())
+    }
+
+    async fn get_job(&self, id: Uuid, user_id: Uuid) -> Result<Option<BackgroundJob>, AppError> {
+        let row = sqlx::query("select * from background_job where id = ? and user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, job_from_row)
+    }
+
+    async fn fail_interrupted_jobs(&self, instance_id: &str) -> Result<(), AppError> {
+        sqlx::query(
+            "update background_job set status = 'failed', error_message = 'Interrupted by backend restart', \
+             finished_at = ? where instance_id = ? and status in ('queued', 'running')",
+        )
+        .bind(Utc::now())
+        .bind(instance_id)
+        .execute(&self.pool)
+        .await
+        .map_err(AppError::storage)?;
+        Ok(())
+    }
+
+    async fn create_api_token(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiToken, AppError> {
+        let sql = format!(
+            "insert into api_token (id, user_id, name, token_hash, token_prefix, scopes, expires_at, created_at) \
+             values (?, ?, ?, ?, ?, ?, ?, ?) returning {}",
+            API_TOKEN_COLUMNS
+        );
+        let row = sqlx::query(&sql)
+            .bind(Uuid::new_v4())
+            .bind(user_id)
+            .bind(name)
+            .bind(token_hash)
+            .bind(token_prefix)
+            .bind(Json(scopes))
+            .bind(expires_at)
+            .bind(Utc::now())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        api_token_from_row(&row).map_err(AppError::storage)
+    }
+
+    async fn list_api_tokens(&self, user_id: Uuid) -> Result<Vec<ApiToken>, AppError> {
+        let sql = format!(
+            "select {} from api_token where user_id = ? order by created_at desc",
+            API_TOKEN_COLUMNS
+        );
+        let rows = sqlx::query(&sql)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        all(rows, api_token_from_row)
+    }
+
+    async fn find_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, AppError> {
+        let sql = format!("select {} from api_token where token_hash = ?", API_TOKEN_COLUMNS);
+        let row = sqlx::query(&sql)
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        one(row, api_token_from_row)
+    }
+
+    async fn touch_api_token(&self, id: Uuid) -> Result<(), AppError> {
+        sqlx::query("update api_token set last_used_at = ? where id = ?")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(())
+    }
+
+    async fn revoke_api_token(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let res = sqlx::query("update api_token set revoked_at = ? where id = ? and user_id = ? and revoked_at is null")
+            .bind(Utc::now())
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(AppError::storage)?;
+        Ok(res.rows_affected() > 0)
+    }
+
+    async fn pending_migrations(&self) -> Result<Vec<SchemaMigration>, AppError> {
+        let mut conn = self.pool.acquire().await.map_err(AppError::storage)?;
+        conn.ensure_migrations_table().await.map_err(AppError::storage)?;
+        let applied: Vec<i64> = conn
+            .list_applied_migrations()
+            .await
+            .map_err(AppError::storage)?
+            .into_iter()
+            .map(|m| m.version)
+            .collect();
+        Ok(super::pending_in(&MIGRATOR, &applied))
+    }
+
+    async fn apply_migrations(&self) -> Result<Vec<SchemaMigration>, AppError> {
+        let pending = self.pending_migrations().await?;
+        MIGRATOR.run(&self.pool).await.map_err(AppError::storage)?;
+        Ok(pending)
+    }
+
+    async fn health_check(&self) -> bool {
+        sqlx::query("select 1").execute(&self.pool).await.is_ok()
+    }
+}
@@ -0,0 +1,1315 @@
+//! `NewsletterStore` over the Supabase REST API (PostgREST) for newsletter_config,
+//! newsletter_run_log, newsletter_section, newsletter_preset, prompt_template, background_job,
+//! approved_users, access_request, user_roles and api_token.
+//! Uses HTTPS only (no direct Postgres), so works on VPS with no DB port/DNS.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{NewsletterCursor, NewsletterListQuery, NewsletterPage, NewsletterSort, NewsletterStore, SchemaMigration};
+use crate::error::AppError;
+use crate::models::{
+    AccessRequest, ApiToken, ApprovedUserRow, BackgroundJob, Blackout, CreateNewsletterConfig, CreateNewsletterPreset, CreateNewsletterSection,
+    NewsletterConfig, NewsletterPreset, NewsletterSection, PromptTemplate, RunLog, UpdateNewsletterSection,
+};
+use crate::validation;
+
+#[derive(Clone)]
+pub struct SupabaseClient {
+    base_url: String,
+    key: String,
+    client: Client,
+}
+
+/// Row shape from PostgREST (send_time_utc is "HH:MM:SS" string).
+#[derive(Debug, Deserialize)]
+struct NewsletterConfigRow {
+    id: Uuid,
+    user_id: Uuid,
+    title: String,
+    topics: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    tone: String,
+    length: String,
+    #[serde(deserialize_with = "deser_time")]
+    send_time_utc: NaiveTime,
+    timezone: String,
+    delivery_email: String,
+    is_active: bool,
+    #[serde(default)]
+    paused_until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    blackouts: Vec<Blackout>,
+    features: serde_json::Value,
+    #[serde(default)]
+    prompt_template_version: Option<i32>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+fn deser_time<'de, D>(d: D) -> Result<NaiveTime, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(d)?;
+    let parts: Vec<&str> = s.trim_end_matches('Z').split(':').collect();
+    let h: u32 = parts.first().and_then(|p| p.parse().ok()).unwrap_or(0);
+    let m: u32 = parts.get(1).and_then(|p| p.parse().ok()).unwrap_or(0);
+    let sec: u32 = parts.get(2).and_then(|p| p.parse().ok()).unwrap_or(0);
+    NaiveTime::from_hms_opt(h, m, sec).ok_or_else(|| serde::de::Error::custom("invalid time"))
+}
+
+impl NewsletterConfigRow {
+    fn into_config(self) -> NewsletterConfig {
+        NewsletterConfig {
+            id: self.id,
+            user_id: self.user_id,
+            title: self.title,
+            topics: self.topics,
+            tags: self.tags,
+            tone: self.tone,
+            length: self.length,
+            send_time_utc: self.send_time_utc,
+            timezone: self.timezone,
+            delivery_email: self.delivery_email,
+            is_active: self.is_active,
+            paused_until: self.paused_until,
+            blackouts: self.blackouts,
+            features: self.features,
+            prompt_template_version: self.prompt_template_version,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+/// Double-quotes a PostgREST filter value (or array element) so `,.:()` and quotes in it are literal.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Query params for the filters of `q`, shared by the page and its count.
+fn newsletter_list_filters(user_id: Uuid, q: &NewsletterListQuery) -> Vec<(&'static str, String)> {
+    let time = |t: NaiveTime| quoted(&t.format("%H:%M:%S").to_string());
+    let mut params = vec![("user_id", format!("eq.{}", user_id))];
+    if let Some(a) = q.is_active {
+        params.push(("is_active", format!("eq.{}", a)));
+    }
+    if let Some(tag) = q.tag.as_deref() {
+        params.push(("tags", format!("cs.{{{}}}", quoted(tag))));
+    }
+    match (q.send_from, q.send_to) {
+        (Some(from), Some(to)) if from > to => params.push((
+            "or",
+            format!("(send_time_utc.gte.{},send_time_utc.lte.{})", time(from), time(to)),
+        )),
+        (from, to) => {
+            if let Some(f) = from {
+                params.push(("send_time_utc", format!("gte.{}", time(f))));
+            }
+            if let Some(t) = to {
+                params.push(("send_time_utc", format!("lte.{}", time(t))));
+            }
+        }
+    }
+    params
+}
+
+/// Keyset condition for rows after `cursor`, as a PostgREST `and=` logic tree.
+fn newsletter_after(cursor: &NewsletterCursor) -> String {
+    let (column, op, value, id) = match cursor {
+        NewsletterCursor::Created(t, id) => ("created_at", "lt", t.to_rfc3339_opts(SecondsFormat::AutoSi, true), id),
+        NewsletterCursor::Title(t, id) => ("title", "gt", t.clone(), id),
+        NewsletterCursor::NextSend(t, id) => ("send_time_utc", "gt", t.format("%H:%M:%S%.f").to_string(), id),
+    };
+    let value = quoted(&value);
+    format!("(or({c}.{op}.{v},and({c}.eq.{v},id.{op}.{id})))", c = column, op = op, v = value, id = id)
+}
+
+impl SupabaseClient {
+    pub fn new(base_url: String, service_role_key: String) -> Self {
+        let client = Client::new();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            key: service_role_key,
+            client,
+        }
+    }
+
+    fn rest_url(&self, path: &str) -> String {
+        format!("{}/rest/v1/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    fn headers(&self) -> reqwest::header::HeaderMap {
+        let mut h = reqwest::header::HeaderMap::new();
+        h.insert(
+            reqwest::header::ACCEPT,
+            "application/json".parse().unwrap(),
+        );
+        h.insert(
+            reqwest::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+        h.insert(
+            "apikey",
+            self.key.parse().unwrap(),
+        );
+        h.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {}", self.key).parse().unwrap(),
+        );
+        h
+    }
+}
+
+#[rocket::async_trait]
+impl NewsletterStore for SupabaseClient {
+    async fn list_newsletters_by_user(
+        &self,
+        user_id: Uuid,
+        q: &NewsletterListQuery,
+    ) -> Result<NewsletterPage, AppError> {
+        let filters = newsletter_list_filters(user_id, q);
+        let order = match q.sort {
+            NewsletterSort::Created => "created_at.desc,id.desc",
+            NewsletterSort::Title => "title.asc,id.asc",
+            NewsletterSort::NextSend => "send_time_utc.asc,id.asc",
+        };
+        let mut params = filters.clone();
+        if let Some(cursor) = q.after.as_ref() {
+            params.push(("and", newsletter_after(cursor)));
+        }
+        params.push(("order", order.to_string()));
+        params.push(("limit", q.limit.to_string()));
+        params.push(("select", "*".to_string()));
+        let res = self
+            .client
+            .get(self.rest_url("newsletter_config"))
+            .headers(self.headers())
+            .query(&params)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+
+        // The total comes back in Content-Range (`0-24/310`, or `*/0`) on a body-less request.
+        let mut params = filters;
+        params.push(("select", "id".to_string()));
+        let res = self
+            .client
+            .head(self.rest_url("newsletter_config"))
+            .headers(self.headers())
+            .header("Prefer", "count=exact")
+            .query(&params)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list count: {}", res.status())));
+        }
+        let total = res
+            .headers()
+            .get(reqwest::header::CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| AppError::Storage("Supabase list count: missing Content-Range".into()))?;
+        Ok(NewsletterPage {
+            items: rows.into_iter().map(|r| r.into_config()).collect(),
+            total,
+        })
+    }
+
+    async fn get_newsletter_by_id(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<NewsletterConfig>, AppError> {
+        let url = format!(
+            "{}?id=eq.{}&user_id=eq.{}&select=*",
+            self.rest_url("newsletter_config"),
+            id,
+            user_id
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase get: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next().map(|r| r.into_config()))
+    }
+
+    async fn create_newsletter(
+        &self,
+        user_id: Uuid,
+        delivery_email: &str,
+        body: &CreateNewsletterConfig,
+    ) -> Result<NewsletterConfig, AppError> {
+        let title = body.title.as_deref().unwrap_or("");
+        let topics = body.topics.clone().unwrap_or_default();
+        let tags = body.tags.clone().unwrap_or_default();
+        let tone = body.tone.as_deref().unwrap_or("neutral").to_string();
+        let length = body.length.as_deref().unwrap_or("medium").to_string();
+        let send_time_utc = body
+            .send_time_utc
+            .as_deref()
+            .and_then(validation::parse_send_time)
+            .unwrap_or_else(|| NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        let timezone = body.timezone.as_deref().unwrap_or("UTC").to_string();
+        let is_active = body.is_active.unwrap_or(true);
+        let paused_until = body.paused_until.as_deref().and_then(validation::parse_paused_until);
+        let blackouts = body.blackouts.clone().unwrap_or_default();
+        let features = body.features.clone().unwrap_or(serde_json::json!({}));
+
+        let payload = serde_json::json!({
+            "user_id": user_id,
+            "title": title,
+            "topics": topics,
+            "tags": tags,
+            "tone": tone,
+            "length": length,
+            "send_time_utc": send_time_utc.format("%H:%M:%S").to_string(),
+            "timezone": timezone,
+            "delivery_email": delivery_email,
+            "is_active": is_active,
+            "paused_until": paused_until.map(|t| t.to_rfc3339()),
+            "blackouts": blackouts,
+            "features": features,
+            "prompt_template_version": body.prompt_template_version,
+        });
+
+        let url = format!("{}?select=*", self.rest_url("newsletter_config"));
+        let res = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!("Supabase create: {} {}", status, body)));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        rows.into_iter()
+            .next()
+            .map(|r| r.into_config())
+            .ok_or_else(|| AppError::Storage("Supabase create: no row returned".into()))
+    }
+
+    async fn save_newsletter(
+        &self,
+        c: &NewsletterConfig,
+        expected_updated_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<NewsletterConfig>, AppError> {
+        let payload = serde_json::json!({
+            "title": c.title,
+            "topics": c.topics,
+            "tags": c.tags,
+            "tone": c.tone,
+            "length": c.length,
+            "send_time_utc": c.send_time_utc.format("%H:%M:%S").to_string(),
+            "timezone": c.timezone,
+            "delivery_email": c.delivery_email,
+            "is_active": c.is_active,
+            "paused_until": c.paused_until.map(|t| t.to_rfc3339()),
+            "blackouts": c.blackouts,
+            "features": c.features,
+            "prompt_template_version": c.prompt_template_version,
+            "updated_at": c.updated_at.to_rfc3339(),
+        });
+
+        let mut url = format!(
+            "{}?id=eq.{}&user_id=eq.{}&select=*",
+            self.rest_url("newsletter_config"),
+            c.id,
+            c.user_id
+        );
+        if let Some(t) = expected_updated_at {
+            url.push_str(&format!("&updated_at=eq.{}", t.to_rfc3339_opts(SecondsFormat::AutoSi, true)));
+        }
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase update: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next().map(|r| r.into_config()))
+    }
+
+    async fn delete_newsletter(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let url = format!(
+            "{}?id=eq.{}&user_id=eq.{}",
+            self.rest_url("newsletter_config"),
+            id,
+            user_id
+        );
+        let res = self
+            .client
+            .delete(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        Ok(res.status() == reqwest::StatusCode::NO_CONTENT || res.status().is_success())
+    }
+
+    /// One RPC (`due_newsletter_configs`) instead of a last-run lookup per newsletter.
+    async fn list_due_newsletter_configs(
+        &self,
+        now: DateTime<Utc>,
+        window_minutes: u32,
+    ) -> Result<Vec<NewsletterConfig>, AppError> {
+        let res = self
+            .client
+            .post(self.rest_url("rpc/due_newsletter_configs"))
+            .headers(self.headers())
+            .json(&serde_json::json!({ "as_of": now, "window_minutes": window_minutes }))
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase due_newsletter_configs: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().map(|r| r.into_config()).collect())
+    }
+
+    async fn insert_run_log(
+        &self,
+        newsletter_config_id: Uuid,
+        status: &str,
+        error_message: Option<&str>,
+        openclaw_response_id: Option<&str>,
+        prompt_template_version: Option<i32>,
+    ) -> Result<(), AppError> {
+        let payload = serde_json::json!({
+            "newsletter_config_id": newsletter_config_id,
+            "status": status,
+            "error_message": error_message,
+            "openclaw_response_id": openclaw_response_id,
+            "prompt_template_version": prompt_template_version,
+        });
+        let url = self.rest_url("newsletter_run_log");
+        let res = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase insert_run_log: {}", res.status())));
+        }
+        Ok(())
+    }
+
+    /// Asks the `schema_version` RPC how far the schema has been migrated; PostgREST does not
+    /// expose `_sqlx_migrations`. A missing function means the schema predates it.
+    async fn pending_migrations(&self) -> Result<Vec<SchemaMigration>, AppError> {
+        let res = self
+            .client
+            .post(self.rest_url("rpc/schema_version"))
+            .headers(self.headers())
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        let version: i64 = if res.status() == reqwest::StatusCode::NOT_FOUND {
+            0
+        } else if res.status().is_success() {
+            res.json().await.map_err(AppError::storage)?
+        } else {
+            return Err(AppError::Storage(format!("Supabase schema_version: {}", res.status())));
+        };
+        let applied: Vec<i64> = super::postgres::MIGRATOR
+            .iter()
+            .map(|m| m.version)
+            .filter(|v| *v <= version)
+            .collect();
+        Ok(super::pending_in(&super::postgres::MIGRATOR, &applied))
+    }
+
+    /// PostgREST cannot run DDL, so migrations are applied out of band with `speedo-backend
+    /// migrate` over a direct connection, which records the versions `schema_version` reads.
+    async fn apply_migrations(&self) -> Result<Vec<SchemaMigration>, AppError> {
+        let pending: Vec<String> = self
+            .pending_migrations()
+            .await?
+            .iter()
+            .map(|m| format!("{} {}", m.version, m.description))
+            .collect();
+        Err(AppError::Storage(format!(
+            "Supabase REST cannot apply migrations ({} pending); run `speedo-backend migrate` with \
+             STORAGE_BACKEND=postgres and DATABASE_URL set to the project's direct connection",
+            pending.join(", ")
+        )))
+    }
+
+    /// Minimal request to check Supabase REST is reachable.
+    async fn health_check(&self) -> bool {
+        let url = format!("{}?select=id&limit=1", self.rest_url("newsletter_config"));
+        self.client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+    }
+
+    /// Backend uses the service role, so RLS is bypassed.
+    async fn is_user_approved(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let url = format!(
+            "{}?user_id=eq.{}&select=user_id",
+            self.rest_url("approved_users"),
+            user_id
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("approved_users lookup: {}", res.status())));
+        }
+        #[derive(Deserialize)]
+        struct Row {
+            #[allow(dead_code)]
+            user_id: Uuid,
+        }
+        let rows: Vec<Row> = res.json().await.map_err(AppError::storage)?;
+        Ok(!rows.is_empty())
+    }
+    /// Built-in sections plus the user's own, in display order.
+    async fn list_sections_for_user(&self, user_id: Uuid) -> Result<Vec<NewsletterSection>, AppError> {
+        let url = format!(
+            "{}?or=(user_id.is.null,user_id.eq.{})&order=sort_order.asc,name.asc&select=*",
+            self.rest_url("newsletter_section"),
+            user_id
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_sections: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    async fn create_section(
+        &self,
+        user_id: Uuid,
+        body: &CreateNewsletterSection,
+    ) -> Result<NewsletterSection, AppError> {
+        let payload = serde_json::json!({
+            "user_id": user_id,
+            "key": body.key,
+            "name": body.name,
+            "description": body.description.as_deref().unwrap_or(""),
+            "default_instructions": body.default_instructions.as_deref().unwrap_or(""),
+            "sort_order": body.sort_order.unwrap_or(100),
+        });
+        let url = format!("{}?select=*", self.rest_url("newsletter_section"));
+        let res = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!("Supabase create_section: {} {}", status, body)));
+        }
+        let rows: Vec<NewsletterSection> = res.json().await.map_err(AppError::storage)?;
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| AppError::Storage("Supabase create_section: no row returned".into()))
+    }
+
+    /// Updates a user-defined section; built-in sections are never matched.
+    async fn update_section(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        body: &UpdateNewsletterSection,
+    ) -> Result<Option<NewsletterSection>, AppError> {
+        let mut payload = serde_json::json!({ "updated_at": Utc::now().to_rfc3339() });
+        if let Some(n) = body.name.as_ref() {
+            payload["name"] = serde_json::json!(n);
+        }
+        if let Some(d) = body.description.as_ref() {
+            payload["description"] = serde_json::json!(d);
+        }
+        if let Some(i) = body.default_instructions.as_ref() {
+            payload["default_instructions"] = serde_json::json!(i);
+        }
+        if let Some(o) = body.sort_order {
+            payload["sort_order"] = serde_json::json!(o);
+        }
+        let url = format!(
+            "{}?id=eq.{}&user_id=eq.{}&select=*",
+            self.rest_url("newsletter_section"),
+            id,
+            user_id
+        );
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase update_section: {}", res.status())));
+        }
+        let rows: Vec<NewsletterSection> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    async fn delete_section(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let url = format!(
+            "{}?id=eq.{}&user_id=eq.{}&select=id",
+            self.rest_url("newsletter_section"),
+            id,
+            user_id
+        );
+        let res = self
+            .client
+            .delete(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase delete_section: {}", res.status())));
+        }
+        let rows: Vec<serde_json::Value> = res.json().await.map_err(AppError::storage)?;
+        Ok(!rows.is_empty())
+    }
+
+    /// The given template version, or the latest when `version` is None.
+    async fn get_prompt_template(&self, version: Option<i32>) -> Result<Option<PromptTemplate>, AppError> {
+        let url = match version {
+            Some(v) => format!("{}?version=eq.{}&select=*", self.rest_url("prompt_template"), v),
+            None => format!(
+                "{}?order=version.desc&limit=1&select=*",
+                self.rest_url("prompt_template")
+            ),
+        };
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase get_prompt_template: {}", res.status())));
+        }
+        let rows: Vec<PromptTemplate> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    async fn list_prompt_templates(&self) -> Result<Vec<PromptTemplate>, AppError> {
+        let url = format!(
+            "{}?order=version.desc&select=*",
+            self.rest_url("prompt_template")
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_prompt_templates: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    /// Every preset, curated first, then newest first.
+    async fn list_presets(&self) -> Result<Vec<NewsletterPreset>, AppError> {
+        let url = format!(
+            "{}?order=curated.desc,created_at.desc,id.desc&select=*",
+            self.rest_url("newsletter_preset")
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_presets: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    async fn get_preset(&self, id: Uuid) -> Result<Option<NewsletterPreset>, AppError> {
+        let url = format!("{}?id=eq.{}&select=*", self.rest_url("newsletter_preset"), id);
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase get_preset: {}", res.status())));
+        }
+        let rows: Vec<NewsletterPreset> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    async fn create_preset(
+        &self,
+        user_id: Uuid,
+        curated: bool,
+        body: &CreateNewsletterPreset,
+    ) -> Result<NewsletterPreset, AppError> {
+        let payload = serde_json::json!({
+            "user_id": user_id,
+            "curated": curated,
+            "name": body.name.trim(),
+            "description": body.description.as_deref().unwrap_or(""),
+            "topics": body.topics.clone().unwrap_or_default(),
+            "tone": body.tone.as_deref().unwrap_or("neutral"),
+            "length": body.length.as_deref().unwrap_or("medium"),
+            "features": body.features.clone().unwrap_or_else(|| serde_json::json!({})),
+            "prompt_template_version": body.prompt_template_version,
+        });
+        let url = format!("{}?select=*", self.rest_url("newsletter_preset"));
+        let res = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!("Supabase create_preset: {} {}", status, body)));
+        }
+        let rows: Vec<NewsletterPreset> = res.json().await.map_err(AppError::storage)?;
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| AppError::Storage("Supabase create_preset: no row returned".into()))
+    }
+
+    async fn delete_preset(&self, id: Uuid, user_id: Option<Uuid>) -> Result<bool, AppError> {
+        let mut url = format!("{}?id=eq.{}&select=id", self.rest_url("newsletter_preset"), id);
+        if let Some(user_id) = user_id {
+            url.push_str(&format!("&user_id=eq.{}&curated=is.false", user_id));
+        }
+        let res = self
+            .client
+            .delete(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase delete_preset: {}", res.status())));
+        }
+        let rows: Vec<serde_json::Value> = res.json().await.map_err(AppError::storage)?;
+        Ok(!rows.is_empty())
+    }
+
+    async fn insert_job(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        kind: &str,
+        newsletter_config_id: Option<Uuid>,
+        slot: Option<NaiveDate>,
+        instance_id: &str,
+    ) -> Result<bool, AppError> {
+        let payload = serde_json::json!({
+            "id": id,
+            "user_id": user_id,
+            "kind": kind,
+            "newsletter_config_id": newsletter_config_id,
+            "status": "queued",
+            "slot": slot,
+            "instance_id": instance_id,
+        });
+        let res = self
+            .client
+            .post(self.rest_url("background_job"))
+            .headers(self.headers())
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        // PostgREST answers a unique violation with 409: the slot is already claimed.
+        if res.status() == reqwest::StatusCode::CONFLICT {
+            return Ok(false);
+        }
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase insert_job: {}", res.status())));
+        }
+        Ok(true)
+    }
+
+    /// Moves a job to `status`, stamping started_at for `running` and finished_at otherwise.
+    async fn update_job_status(
+        &self,
+        id: Uuid,
+        status: &str,
+        error_message: Option<&str>,
+    ) -> Result<(), AppError> {
+        let now = Utc::now().to_rfc3339();
+        let payload = if status == "running" {
+            serde_json::json!({ "status": status, "started_at": now })
+        } else {
+            serde_json::json!({ "status": status, "error_message": error_message, "finished_at": now })
+        };
+        let url = format!("{}?id=eq.{}", self.rest_url("background_job"), id);
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase update_job_status: {}", res.status())));
+        }
+        Ok(())
+    }
+
+    async fn get_job(&self, id: Uuid, user_id: Uuid) -> Result<Option<BackgroundJob>, AppError> {
+        let url = format!(
+            "{}?id=eq.{}&user_id=eq.{}&select=*",
+            self.rest_url("background_job"),
+            id,
+            user_id
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase get_job: {}", res.status())));
+        }
+        let rows: Vec<BackgroundJob> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    async fn fail_interrupted_jobs(&self, instance_id: &str) -> Result<(), AppError> {
+        let url = format!("{}?status=in.(queued,running)", self.rest_url("background_job"));
+        let payload = serde_json::json!({
+            "status": "failed",
+            "error_message": "Interrupted by backend restart",
+            "finished_at": Utc::now().to_rfc3339(),
+        });
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .query(&[("instance_id", format!("eq.{}", quoted(instance_id)))])
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase fail_interrupted_jobs: {}", res.status())));
+        }
+        Ok(())
+    }
+
+    /// True if the user has `role` in user_roles.
+    async fn user_has_role(&self, user_id: Uuid, role: &str) -> Result<bool, AppError> {
+        let url = format!(
+            "{}?user_id=eq.{}&role=eq.{}&select=user_id",
+            self.rest_url("user_roles"),
+            user_id,
+            role
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("user_roles lookup: {}", res.status())));
+        }
+        let rows: Vec<serde_json::Value> = res.json().await.map_err(AppError::storage)?;
+        Ok(!rows.is_empty())
+    }
+
+    /// Adds the user to approved_users; approving an already-approved user is a no-op.
+    async fn approve_user(&self, user_id: Uuid, approved_by: Option<Uuid>) -> Result<(), AppError> {
+        let payload = serde_json::json!({ "user_id": user_id, "approved_by": approved_by });
+        let res = self
+            .client
+            .post(self.rest_url("approved_users"))
+            .headers(self.headers())
+            .header("Prefer", "resolution=ignore-duplicates")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase approve_user: {}", res.status())));
+        }
+        Ok(())
+    }
+
+    /// Removes the user from approved_users. Returns false if they were not approved.
+    async fn revoke_user(&self, user_id: Uuid) -> Result<bool, AppError> {
+        let url = format!(
+            "{}?user_id=eq.{}&select=user_id",
+            self.rest_url("approved_users"),
+            user_id
+        );
+        let res = self
+            .client
+            .delete(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase revoke_user: {}", res.status())));
+        }
+        let rows: Vec<serde_json::Value> = res.json().await.map_err(AppError::storage)?;
+        Ok(!rows.is_empty())
+    }
+
+    async fn list_approved_users(&self) -> Result<Vec<ApprovedUserRow>, AppError> {
+        let url = format!(
+            "{}?order=approved_at.desc&select=*",
+            self.rest_url("approved_users")
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_approved_users: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    async fn create_access_request(
+        &self,
+        user_id: Uuid,
+        email: &str,
+        message: &str,
+    ) -> Result<AccessRequest, AppError> {
+        let payload = serde_json::json!({
+            "user_id": user_id,
+            "email": email,
+            "message": message,
+        });
+        let url = format!("{}?select=*", self.rest_url("access_request"));
+        let res = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!("Supabase create_access_request: {} {}", status, body)));
+        }
+        let rows: Vec<AccessRequest> = res.json().await.map_err(AppError::storage)?;
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| AppError::Storage("Supabase create_access_request: no row returned".into()))
+    }
+
+    /// The user's most recent access request, if any.
+    async fn latest_access_request(&self, user_id: Uuid) -> Result<Option<AccessRequest>, AppError> {
+        let url = format!(
+            "{}?user_id=eq.{}&order=created_at.desc&limit=1&select=*",
+            self.rest_url("access_request"),
+            user_id
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase latest_access_request: {}", res.status())));
+        }
+        let rows: Vec<AccessRequest> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    async fn get_access_request(&self, id: Uuid) -> Result<Option<AccessRequest>, AppError> {
+        let url = format!("{}?id=eq.{}&select=*", self.rest_url("access_request"), id);
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase get_access_request: {}", res.status())));
+        }
+        let rows: Vec<AccessRequest> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// Access requests, oldest first, optionally filtered by status.
+    async fn list_access_requests(&self, status: Option<&str>) -> Result<Vec<AccessRequest>, AppError> {
+        let mut url = format!(
+            "{}?order=created_at.asc&select=*",
+            self.rest_url("access_request")
+        );
+        if let Some(s) = status {
+            url.push_str(&format!("&status=eq.{}", s));
+        }
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_access_requests: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    /// Moves a pending request to `status`. Returns None if it does not exist or is not pending.
+    async fn review_access_request(
+        &self,
+        id: Uuid,
+        status: &str,
+        reviewed_by: Uuid,
+    ) -> Result<Option<AccessRequest>, AppError> {
+        let payload = serde_json::json!({
+            "status": status,
+            "reviewed_by": reviewed_by,
+            "reviewed_at": Utc::now().to_rfc3339(),
+        });
+        let url = format!(
+            "{}?id=eq.{}&status=eq.pending&select=*",
+            self.rest_url("access_request"),
+            id
+        );
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase review_access_request: {}", res.status())));
+        }
+        let rows: Vec<AccessRequest> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    /// Any user's newsletters (admin use), newest first, optionally filtered.
+    async fn list_all_newsletters(
+        &self,
+        user_id: Option<Uuid>,
+        is_active: Option<bool>,
+    ) -> Result<Vec<NewsletterConfig>, AppError> {
+        let mut url = format!(
+            "{}?order=created_at.desc&select=*",
+            self.rest_url("newsletter_config")
+        );
+        if let Some(u) = user_id {
+            url.push_str(&format!("&user_id=eq.{}", u));
+        }
+        if let Some(a) = is_active {
+            url.push_str(&format!("&is_active=eq.{}", a));
+        }
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_all: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().map(|r| r.into_config()).collect())
+    }
+
+    /// Newsletter by id regardless of owner (admin use).
+    async fn get_newsletter_any(&self, id: Uuid) -> Result<Option<NewsletterConfig>, AppError> {
+        let url = format!("{}?id=eq.{}&select=*", self.rest_url("newsletter_config"), id);
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase get_any: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next().map(|r| r.into_config()))
+    }
+
+    /// Sets is_active on any user's newsletter (admin use).
+    async fn set_newsletter_active(&self, id: Uuid, is_active: bool) -> Result<Option<NewsletterConfig>, AppError> {
+        let payload = serde_json::json!({
+            "is_active": is_active,
+            "updated_at": Utc::now().to_rfc3339(),
+        });
+        let url = format!("{}?id=eq.{}&select=*", self.rest_url("newsletter_config"), id);
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase set_active: {}", res.status())));
+        }
+        let rows: Vec<NewsletterConfigRow> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next().map(|r| r.into_config()))
+    }
+
+    /// Run logs across all users, newest first.
+    async fn list_run_logs(
+        &self,
+        newsletter_config_id: Option<Uuid>,
+        status: Option<&str>,
+        limit: u32,
+    ) -> Result<Vec<RunLog>, AppError> {
+        let mut url = format!(
+            "{}?order=run_at.desc&limit={}&select=*",
+            self.rest_url("newsletter_run_log"),
+            limit
+        );
+        if let Some(id) = newsletter_config_id {
+            url.push_str(&format!("&newsletter_config_id=eq.{}", id));
+        }
+        if let Some(s) = status {
+            url.push_str(&format!("&status=eq.{}", s));
+        }
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_run_logs: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    /// One RPC (`latest_run_logs`) instead of a lookup per newsletter.
+    async fn latest_run_logs(&self, newsletter_config_ids: &[Uuid]) -> Result<Vec<RunLog>, AppError> {
+        if newsletter_config_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let res = self
+            .client
+            .post(self.rest_url("rpc/latest_run_logs"))
+            .headers(self.headers())
+            .json(&serde_json::json!({ "config_ids": newsletter_config_ids }))
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase latest_run_logs: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    async fn create_api_token(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        token_hash: &str,
+        token_prefix: &str,
+        scopes: &[String],
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<ApiToken, AppError> {
+        let payload = serde_json::json!({
+            "user_id": user_id,
+            "name": name,
+            "token_hash": token_hash,
+            "token_prefix": token_prefix,
+            "scopes": scopes,
+            "expires_at": expires_at.map(|t| t.to_rfc3339()),
+        });
+        let url = format!("{}?select={}", self.rest_url("api_token"), API_TOKEN_COLUMNS);
+        let res = self
+            .client
+            .post(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&payload)
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            let status = res.status();
+            let body = res.text().await.unwrap_or_default();
+            return Err(AppError::Storage(format!("Supabase create_api_token: {} {}", status, body)));
+        }
+        let rows: Vec<ApiToken> = res.json().await.map_err(AppError::storage)?;
+        rows.into_iter()
+            .next()
+            .ok_or_else(|| AppError::Storage("Supabase create_api_token: no row returned".into()))
+    }
+
+    async fn list_api_tokens(&self, user_id: Uuid) -> Result<Vec<ApiToken>, AppError> {
+        let url = format!(
+            "{}?user_id=eq.{}&order=created_at.desc&select={}",
+            self.rest_url("api_token"),
+            user_id,
+            API_TOKEN_COLUMNS
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase list_api_tokens: {}", res.status())));
+        }
+        res.json().await.map_err(AppError::storage)
+    }
+
+    /// Looks a token up by hash, including revoked and expired ones; the caller decides.
+    async fn find_api_token_by_hash(&self, token_hash: &str) -> Result<Option<ApiToken>, AppError> {
+        let url = format!(
+            "{}?token_hash=eq.{}&select={}",
+            self.rest_url("api_token"),
+            token_hash,
+            API_TOKEN_COLUMNS
+        );
+        let res = self
+            .client
+            .get(&url)
+            .headers(self.headers())
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("api_token lookup: {}", res.status())));
+        }
+        let rows: Vec<ApiToken> = res.json().await.map_err(AppError::storage)?;
+        Ok(rows.into_iter().next())
+    }
+
+    async fn touch_api_token(&self, id: Uuid) -> Result<(), AppError> {
+        let url = format!("{}?id=eq.{}", self.rest_url("api_token"), id);
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .json(&serde_json::json!({ "last_used_at": Utc::now().to_rfc3339() }))
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase touch_api_token: {}", res.status())));
+        }
+        Ok(())
+    }
+
+    /// Marks the user's token revoked. Returns false if no active token matched.
+    async fn revoke_api_token(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let url = format!(
+            "{}?id=eq.{}&user_id=eq.{}&revoked_at=is.null&select=id",
+            self.rest_url("api_token"),
+            id,
+            user_id
+        );
+        let res = self
+            .client
+            .patch(&url)
+            .headers(self.headers())
+            .header("Prefer", "return=representation")
+            .json(&serde_json::json!({ "revoked_at": Utc::now().to_rfc3339() }))
+            .send()
+            .await
+            .map_err(AppError::storage)?;
+        if !res.status().is_success() {
+            return Err(AppError::Storage(format!("Supabase revoke_api_token: {}", res.status())));
+        }
+        let rows: Vec<serde_json::Value> = res.json().await.map_err(AppError::storage)?;
+        Ok(!rows.is_empty())
+    }
+}
+
+/// api_token columns safe to return (everything but token_hash).
+const API_TOKEN_COLUMNS: &str =
+    "id,user_id,name,token_prefix,scopes,expires_at,last_used_at,revoked_at,created_at";
@@ -0,0 +1,367 @@
+//! Field-level validation for newsletter create/update bodies. Runs before any Supabase call;
+//! failures become a 422 whose `details` maps each field to its messages.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use crate::error::AppError;
+use crate::models::{Blackout, BlackoutRepeat, CreateNewsletterConfig, CreateNewsletterPreset, Patch, PatchNewsletterConfig, UpdateNewsletterConfig};
+
+pub const LENGTHS: [&str; 3] = ["short", "medium", "long"];
+pub const MAX_TITLE_LEN: usize = 200;
+pub const MAX_TOPICS: usize = 20;
+pub const MAX_TOPIC_LEN: usize = 100;
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_TONE_LEN: usize = 50;
+pub const MAX_TIMEZONE_LEN: usize = 64;
+pub const MAX_CUSTOM_REQUEST_LEN: usize = 2000;
+pub const MAX_PRESET_DESCRIPTION_LEN: usize = 1000;
+pub const MAX_BLACKOUTS: usize = 100;
+pub const MAX_BLACKOUT_REASON_LEN: usize = 200;
+
+/// Messages collected per field, rendered as `{ "<field>": ["<message>", ...] }`.
+#[derive(Default)]
+pub struct FieldErrors(BTreeMap<String, Vec<String>>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.entry(field.into()).or_default().push(message.into());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_json(self) -> serde_json::Value {
+        serde_json::json!(self.0)
+    }
+
+    pub fn into_result(self) -> Result<(), AppError> {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        Err(AppError::Validation {
+            message: "Request has invalid fields".into(),
+            details: self.into_json(),
+        })
+    }
+}
+
+/// Accepts `HH:MM` or `HH:MM:SS` (24-hour).
+pub fn parse_send_time(s: &str) -> Option<NaiveTime> {
+    let s = s.trim();
+    NaiveTime::parse_from_str(s, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M:%S"))
+        .ok()
+}
+
+/// Accepts an RFC 3339 instant or a `YYYY-MM-DD` date, meaning the start of that UTC day.
+pub fn parse_paused_until(s: &str) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .map(|d| d.and_time(NaiveTime::MIN).and_utc())
+        })
+}
+
+pub fn is_valid_email(s: &str) -> bool {
+    s.len() <= 254 && lettre::Address::from_str(s).is_ok()
+}
+
+pub fn newsletter_create(body: &CreateNewsletterConfig) -> Result<(), AppError> {
+    let mut errors = FieldErrors::default();
+    check_newsletter_create(&mut errors, body);
+    errors.into_result()
+}
+
+/// The checks of `newsletter_create`, collected into `errors` (import reports them per item).
+/// Unlike an update, a new newsletter must have a title and at least one topic.
+pub fn check_newsletter_create(errors: &mut FieldErrors, body: &CreateNewsletterConfig) {
+    if body.title.is_none() {
+        errors.add("title", "is required");
+    }
+    if body.topics.is_none() {
+        errors.add("topics", "must have at least one entry");
+    }
+    check_newsletter_fields(
+        errors,
+        NewsletterFields {
+            title: body.title.as_deref(),
+            topics: body.topics.as_deref(),
+            tags: body.tags.as_deref(),
+            tone: body.tone.as_deref(),
+            length: body.length.as_deref(),
+            send_time_utc: body.send_time_utc.as_deref(),
+            timezone: body.timezone.as_deref(),
+            delivery_email: body.delivery_email.as_deref(),
+            paused_until: body.paused_until.as_deref(),
+            blackouts: body.blackouts.as_deref(),
+            features: body.features.as_ref(),
+        },
+    );
+}
+
+pub fn newsletter_update(body: &UpdateNewsletterConfig) -> Result<(), AppError> {
+    let mut errors = FieldErrors::default();
+    check_newsletter_fields(
+        &mut errors,
+        NewsletterFields {
+            title: body.title.as_deref(),
+            topics: body.topics.as_deref(),
+            tags: body.tags.as_deref(),
+            tone: body.tone.as_deref(),
+            length: body.length.as_deref(),
+            send_time_utc: body.send_time_utc.as_deref(),
+            timezone: body.timezone.as_deref(),
+            delivery_email: body.delivery_email.as_deref(),
+            paused_until: body.paused_until.as_deref(),
+            blackouts: body.blackouts.as_deref(),
+            features: body.features.as_ref(),
+        },
+    );
+    errors.into_result()
+}
+
+/// Like update, plus the PATCH-only rules: title, topics and delivery_email cannot be cleared
+/// and `features` must be a merge-patch object (or null to clear it).
+pub fn newsletter_patch(body: &PatchNewsletterConfig) -> Result<(), AppError> {
+    let mut errors = FieldErrors::default();
+    check_newsletter_fields(
+        &mut errors,
+        NewsletterFields {
+            title: body.title.as_value().map(String::as_str),
+            topics: body.topics.as_value().map(Vec::as_slice),
+            tags: body.tags.as_value().map(Vec::as_slice),
+            tone: body.tone.as_value().map(String::as_str),
+            length: body.length.as_value().map(String::as_str),
+            send_time_utc: body.send_time_utc.as_value().map(String::as_str),
+            timezone: body.timezone.as_value().map(String::as_str),
+            delivery_email: body.delivery_email.as_value().map(String::as_str),
+            paused_until: body.paused_until.as_value().map(String::as_str),
+            blackouts: body.blackouts.as_value().map(Vec::as_slice),
+            features: body.features.as_value(),
+        },
+    );
+    if matches!(body.title, Patch::Null) {
+        errors.add("title", "cannot be cleared");
+    }
+    if matches!(body.topics, Patch::Null) {
+        errors.add("topics", "cannot be cleared");
+    }
+    if matches!(body.delivery_email, Patch::Null) {
+        errors.add("delivery_email", "cannot be cleared");
+    }
+    if body.features.as_value().is_some_and(|f| !f.is_object()) {
+        errors.add("features", "must be an object or null");
+    }
+    errors.into_result()
+}
+
+/// A preset's name becomes the newsletter title, so it follows the title rules; like a new
+/// newsletter, it needs at least one topic.
+pub fn preset_create(body: &CreateNewsletterPreset) -> Result<(), AppError> {
+    let mut errors = FieldErrors::default();
+    if body.topics.is_none() {
+        errors.add("topics", "must have at least one entry");
+    }
+    check_newsletter_fields(
+        &mut errors,
+        NewsletterFields {
+            title: Some(&body.name),
+            topics: body.topics.as_deref(),
+            tags: None,
+            tone: body.tone.as_deref(),
+            length: body.length.as_deref(),
+            send_time_utc: None,
+            timezone: None,
+            delivery_email: None,
+            paused_until: None,
+            blackouts: None,
+            features: body.features.as_ref(),
+        },
+    );
+    if let Some(name) = errors.0.remove("title") {
+        errors.0.insert("name".into(), name);
+    }
+    if body
+        .description
+        .as_deref()
+        .is_some_and(|d| d.chars().count() > MAX_PRESET_DESCRIPTION_LEN)
+    {
+        errors.add(
+            "description",
+            format!("must be at most {} characters", MAX_PRESET_DESCRIPTION_LEN),
+        );
+    }
+    errors.into_result()
+}
+
+/// The fields shared by create and update; `None` means "not supplied".
+struct NewsletterFields<'a> {
+    title: Option<&'a str>,
+    topics: Option<&'a [String]>,
+    tags: Option<&'a [String]>,
+    tone: Option<&'a str>,
+    length: Option<&'a str>,
+    send_time_utc: Option<&'a str>,
+    timezone: Option<&'a str>,
+    delivery_email: Option<&'a str>,
+    paused_until: Option<&'a str>,
+    blackouts: Option<&'a [Blackout]>,
+    features: Option<&'a serde_json::Value>,
+}
+
+fn check_newsletter_fields(errors: &mut FieldErrors, f: NewsletterFields<'_>) {
+    if let Some(title) = f.title {
+        if title.trim().is_empty() {
+            errors.add("title", "must not be empty");
+        } else if title.chars().count() > MAX_TITLE_LEN {
+            errors.add("title", format!("must be at most {} characters", MAX_TITLE_LEN));
+        }
+    }
+    if let Some(topics) = f.topics {
+        if topics.is_empty() {
+            errors.add("topics", "must have at least one entry");
+        } else if topics.len() > MAX_TOPICS {
+            errors.add("topics", format!("must have at most {} entries", MAX_TOPICS));
+        }
+        for (i, topic) in topics.iter().enumerate() {
+            let field = format!("topics[{}]", i);
+            if topic.trim().is_empty() {
+                errors.add(field, "must not be empty");
+            } else if topic.chars().count() > MAX_TOPIC_LEN {
+                errors.add(field, format!("must be at most {} characters", MAX_TOPIC_LEN));
+            }
+        }
+    }
+    if let Some(tags) = f.tags {
+        if tags.len() > MAX_TAGS {
+            errors.add("tags", format!("must have at most {} entries", MAX_TAGS));
+        }
+        for (i, tag) in tags.iter().enumerate() {
+            let field = format!("tags[{}]", i);
+            if tag.trim().is_empty() {
+                errors.add(field, "must not be empty");
+            } else if tag.chars().count() > MAX_TAG_LEN {
+                errors.add(field, format!("must be at most {} characters", MAX_TAG_LEN));
+            }
+        }
+    }
+    if let Some(tone) = f.tone {
+        if tone.trim().is_empty() {
+            errors.add("tone", "must not be empty");
+        } else if tone.chars().count() > MAX_TONE_LEN {
+            errors.add("tone", format!("must be at most {} characters", MAX_TONE_LEN));
+        }
+    }
+    if let Some(length) = f.length {
+        if !LENGTHS.contains(&length) {
+            errors.add("length", format!("must be one of: {}", LENGTHS.join(", ")));
+        }
+    }
+    if let Some(t) = f.send_time_utc {
+        if parse_send_time(t).is_none() {
+            errors.add("send_time_utc", "must be a 24-hour time as HH:MM");
+        }
+    }
+    if let Some(tz) = f.timezone {
+        if tz.trim().is_empty() || tz.len() > MAX_TIMEZONE_LEN {
+            errors.add("timezone", format!("must be 1-{} characters", MAX_TIMEZONE_LEN));
+        }
+    }
+    if let Some(email) = f.delivery_email {
+        if !is_valid_email(email) {
+            errors.add("delivery_email", "must be a valid email address");
+        }
+    }
+    if let Some(t) = f.paused_until {
+        if parse_paused_until(t).is_none() {
+            errors.add("paused_until", "must be an RFC 3339 time or a YYYY-MM-DD date");
+        }
+    }
+    if let Some(blackouts) = f.blackouts {
+        if blackouts.len() > MAX_BLACKOUTS {
+            errors.add("blackouts", format!("must have at most {} entries", MAX_BLACKOUTS));
+        }
+        for (i, b) in blackouts.iter().enumerate() {
+            check_blackout(errors, &format!("blackouts[{}]", i), b);
+        }
+    }
+    if let Some(map) = f.features.and_then(|v| v.as_object()) {
+        for (key, value) in map {
+            let too_long = value
+                .get("custom_request")
+                .and_then(|c| c.as_str())
+                .is_some_and(|c| c.chars().count() > MAX_CUSTOM_REQUEST_LEN);
+            if too_long {
+                errors.add(
+                    format!("features.{}.custom_request", key),
+                    format!("must be at most {} characters", MAX_CUSTOM_REQUEST_LEN),
+                );
+            }
+        }
+    }
+}
+
+/// Also used for blackouts read from an iCalendar file, which report under their own index.
+pub fn check_blackout(errors: &mut FieldErrors, field: &str, b: &Blackout) {
+    let span = (b.end - b.start).num_days();
+    if span < 0 {
+        errors.add(field, "end must not be before start");
+    }
+    match b.repeat {
+        Some(BlackoutRepeat::Weekly) if span >= 7 => errors.add(field, "a weekly window must be shorter than a week"),
+        Some(BlackoutRepeat::Yearly) if span >= 365 => errors.add(field, "a yearly window must be shorter than a year"),
+        None if b.until.is_some() => errors.add(field, "until only applies with repeat"),
+        _ => {}
+    }
+    if b.until.is_some_and(|u| u < b.start) {
+        errors.add(field, "until must not be before start");
+    }
+    if b.reason.chars().count() > MAX_BLACKOUT_REASON_LEN {
+        errors.add(field, format!("reason must be at most {} characters", MAX_BLACKOUT_REASON_LEN));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patch(body: serde_json::Value) -> Result<(), AppError> {
+        newsletter_patch(&serde_json::from_value(body).unwrap())
+    }
+
+    fn field_errors(result: Result<(), AppError>) -> serde_json::Value {
+        match result {
+            Err(AppError::Validation { details, .. }) => details,
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn patch_cannot_clear_title_or_topics() {
+        let details = field_errors(patch(serde_json::json!({"title": null, "topics": null})));
+        assert_eq!(details["title"], serde_json::json!(["cannot be cleared"]));
+        assert_eq!(details["topics"], serde_json::json!(["cannot be cleared"]));
+    }
+
+    #[test]
+    fn supplied_topics_must_not_be_empty() {
+        let update: UpdateNewsletterConfig = serde_json::from_value(serde_json::json!({"topics": []})).unwrap();
+        let details = field_errors(newsletter_update(&update));
+        assert_eq!(details["topics"], serde_json::json!(["must have at least one entry"]));
+        let details = field_errors(patch(serde_json::json!({"topics": []})));
+        assert_eq!(details["topics"], serde_json::json!(["must have at least one entry"]));
+    }
+
+    #[test]
+    fn patch_may_omit_title_and_topics() {
+        assert!(patch(serde_json::json!({"tone": "casual"})).is_ok());
+    }
+}
@@ -2,7 +2,7 @@ import { useEffect, useState } from 'react';
 import type { ReactNode } from 'react';
 import type { Session } from '@supabase/supabase-js';
 import { supabase } from '../supabaseClient';
-import { getApprovalStatus } from '../lib/api';
+import { getApprovalStatus, requestAccess } from '../lib/api';
 import { Link, useNavigate } from 'react-router-dom';
 import logo from '@assets/logos/speedologo.svg';
 
@@ -14,6 +14,7 @@ export function AuthGate({ children }: AuthGateProps) {
   const navigate = useNavigate();
   const [session, setSession] = useState<Session | null>(null);
   const [approved, setApproved] = useState<boolean | null>(null);
+  const [accessRequest, setAccessRequest] = useState<string | null>(null);
   const [loading, setLoading] = useState(true);
   const [email, setEmail] = useState('');
   const [password, setPassword] = useState('');
@@ -40,10 +41,22 @@ export function AuthGate({ children }: AuthGateProps) {
       return;
     }
     getApprovalStatus()
-      .then(({ approved: a }) => setApproved(a))
+      .then(({ approved: a, access_request }) => {
+        setApproved(a);
+        setAccessRequest(access_request);
+      })
       .catch(() => setApproved(false));
   }, [session]);
 
+  const handleRequestAccess = async () => {
+    try {
+      const r = await requestAccess();
+      setAccessRequest(r.status);
+    } catch (err) {
+      setAuthError(err instanceof Error ? err.message : 'Request failed');
+    }
+  };
+
   const handleSubmit = async (e: React.FormEvent) => {
     e.preventDefault();
     setAuthError(null);
@@ -135,8 +148,16 @@ export function AuthGate({ children }: AuthGateProps) {
           <div className="auth-page">
             <h1>Thanks for registering</h1>
             <p>
-              In order to keep costs down for now, only manually approved users get access. Standby—we&apos;ll enable your account soon.
+              In order to keep costs down for now, only manually approved users get access.
             </p>
+            {authError && <p className="error">{authError}</p>}
+            {accessRequest === 'pending' ? (
+              <p>Your access request is pending. Standby—we&apos;ll email you once your account is enabled.</p>
+            ) : (
+              <button type="button" onClick={handleRequestAccess}>
+                Request access
+              </button>
+            )}
           </div>
         </main>
       </>
@@ -1,5 +1,5 @@
 import { supabase } from '../supabaseClient';
-import type { NewsletterConfig } from '../types';
+import type { Blackout, NewsletterConfig, NewsletterPreset, NewsletterSection, ScheduledSend, Usage } from '../types';
 
 const API_BASE = (import.meta.env.VITE_API_BASE_URL ?? '').replace(/\/$/, '');
 
@@ -14,10 +14,22 @@ async function getAuthHeaders(): Promise<HeadersInit> {
   return headers;
 }
 
+/** Backend errors are `{ error: { code, message, details } }`; fall back to the raw body. */
+async function errorMessage(res: Response): Promise<string> {
+  const text = await res.text().catch(() => '');
+  try {
+    const parsed = JSON.parse(text);
+    if (parsed?.error?.message) return parsed.error.message;
+  } catch {
+    // not JSON
+  }
+  return text || res.statusText;
+}
+
 export async function apiGet<T>(path: string): Promise<T> {
   const headers = await getAuthHeaders();
   const res = await fetch(`${API_BASE}${path}`, { headers });
-  if (!res.ok) throw new Error(await res.text().catch(() => res.statusText));
+  if (!res.ok) throw new Error(await errorMessage(res));
   return res.json();
 }
 
@@ -28,18 +40,29 @@ export async function apiPost<T>(path: string, body: unknown): Promise<T> {
     headers,
     body: JSON.stringify(body),
   });
-  if (!res.ok) throw new Error(await res.text().catch(() => res.statusText));
+  if (!res.ok) throw new Error(await errorMessage(res));
   return res.json();
 }
 
-export async function apiPut<T>(path: string, body: unknown): Promise<T> {
-  const headers = await getAuthHeaders();
+export async function apiPut<T>(path: string, body: unknown, extraHeaders?: Record<string, string>): Promise<T> {
+  const headers = { ...((await getAuthHeaders()) as Record<string, string>), ...extraHeaders };
   const res = await fetch(`${API_BASE}${path}`, {
     method: 'PUT',
     headers,
     body: JSON.stringify(body),
   });
-  if (!res.ok) throw new Error(await res.text().catch(() => res.statusText));
+  if (!res.ok) throw new Error(await errorMessage(res));
+  return res.json();
+}
+
+export async function apiPatch<T>(path: string, body: unknown, extraHeaders?: Record<string, string>): Promise<T> {
+  const headers = { ...((await getAuthHeaders()) as Record<string, string>), ...extraHeaders };
+  const res = await fetch(`${API_BASE}${path}`, {
+    method: 'PATCH',
+    headers,
+    body: JSON.stringify(body),
+  });
+  if (!res.ok) throw new Error(await errorMessage(res));
   return res.json();
 }
 
@@ -49,11 +72,54 @@ export async function apiDelete(path: string): Promise<void> {
     method: 'DELETE',
     headers,
   });
-  if (!res.ok) throw new Error(await res.text().catch(() => res.statusText));
+  if (!res.ok) throw new Error(await errorMessage(res));
+}
+
+export type NewsletterListParams = {
+  sort?: 'created' | 'title' | 'next_send';
+  limit?: number;
+  active?: boolean;
+  tag?: string;
+  send_from?: string;
+  send_to?: string;
+};
+
+export type NewsletterPage = {
+  items: NewsletterConfig[];
+  total: number;
+  nextCursor: string | null;
+};
+
+/** One page of the list; pass `nextCursor` back as `cursor` for the next. */
+export async function listNewslettersPage(
+  params: NewsletterListParams = {},
+  cursor?: string,
+): Promise<NewsletterPage> {
+  const query = new URLSearchParams();
+  for (const [key, value] of Object.entries(params)) {
+    if (value !== undefined) query.set(key, String(value));
+  }
+  if (cursor) query.set('cursor', cursor);
+  const headers = await getAuthHeaders();
+  const res = await fetch(`${API_BASE}/api/me/newsletters?${query}`, { headers });
+  if (!res.ok) throw new Error(await errorMessage(res));
+  return {
+    items: await res.json(),
+    total: Number(res.headers.get('X-Total-Count') ?? 0),
+    nextCursor: res.headers.get('X-Next-Cursor'),
+  };
 }
 
-export async function listNewsletters(): Promise<NewsletterConfig[]> {
-  return apiGet<NewsletterConfig[]>('/api/me/newsletters');
+/** Every newsletter matching `params`, following cursors page by page. */
+export async function listNewsletters(params: NewsletterListParams = {}): Promise<NewsletterConfig[]> {
+  const all: NewsletterConfig[] = [];
+  let cursor: string | undefined;
+  do {
+    const page = await listNewslettersPage(params, cursor);
+    all.push(...page.items);
+    cursor = page.nextCursor ?? undefined;
+  } while (cursor);
+  return all;
 }
 
 export async function getNewsletter(id: string): Promise<NewsletterConfig> {
@@ -63,12 +129,15 @@ export async function getNewsletter(id: string): Promise<NewsletterConfig> {
 export type CreateNewsletterBody = {
   title?: string;
   topics?: string[];
+  tags?: string[];
   tone?: string;
   length?: string;
   send_time_utc?: string;
   timezone?: string;
   delivery_email?: string;
   is_active?: boolean;
+  paused_until?: string;
+  blackouts?: Blackout[];
   features?: Record<string, { enabled: boolean; custom_request: string }>;
 };
 
@@ -76,29 +145,208 @@ export async function createNewsletter(body: CreateNewsletterBody): Promise<News
   return apiPost<NewsletterConfig>('/api/me/newsletters', body);
 }
 
-export async function updateNewsletter(id: string, body: CreateNewsletterBody): Promise<NewsletterConfig> {
-  return apiPut<NewsletterConfig>(`/api/me/newsletters/${id}`, body);
+/** Pass the loaded `updated_at` so the save fails (409) instead of overwriting a newer edit. */
+export async function updateNewsletter(
+  id: string,
+  body: CreateNewsletterBody,
+  loadedUpdatedAt?: string,
+): Promise<NewsletterConfig> {
+  const headers = loadedUpdatedAt ? { 'If-Match': `"${loadedUpdatedAt}"` } : undefined;
+  return apiPut<NewsletterConfig>(`/api/me/newsletters/${id}`, body, headers);
+}
+
+/** Partial update: omitted fields are kept, `null` resets one, and `features` is merged key by key. */
+export type PatchNewsletterBody = {
+  title?: string | null;
+  topics?: string[] | null;
+  tags?: string[] | null;
+  tone?: string | null;
+  length?: string | null;
+  send_time_utc?: string | null;
+  timezone?: string | null;
+  delivery_email?: string;
+  is_active?: boolean | null;
+  paused_until?: string | null;
+  blackouts?: Blackout[] | null;
+  features?: Record<string, { enabled?: boolean; custom_request?: string | null } | null> | null;
+  prompt_template_version?: number | null;
+};
+
+export async function patchNewsletter(
+  id: string,
+  body: PatchNewsletterBody,
+  loadedUpdatedAt?: string,
+): Promise<NewsletterConfig> {
+  const headers = loadedUpdatedAt ? { 'If-Match': `"${loadedUpdatedAt}"` } : undefined;
+  return apiPatch<NewsletterConfig>(`/api/me/newsletters/${id}`, body, headers);
+}
+
+/** Copies a newsletter as a new inactive one; `overlay` changes the copy before it is saved. */
+export async function duplicateNewsletter(
+  id: string,
+  overlay?: CreateNewsletterBody,
+): Promise<NewsletterConfig> {
+  return apiPost<NewsletterConfig>(`/api/me/newsletters/${id}/duplicate`, overlay ?? {});
+}
+
+/** Adds the events of an .ics file to the blackouts; `replace` swaps the whole list instead. */
+export async function importBlackouts(id: string, ics: string, replace = false): Promise<NewsletterConfig> {
+  const headers = { ...(await getAuthHeaders()), 'Content-Type': 'text/calendar' };
+  const res = await fetch(`${API_BASE}/api/me/newsletters/${id}/blackouts/ical?replace=${replace}`, {
+    method: 'POST',
+    headers,
+    body: ics,
+  });
+  if (!res.ok) throw new Error(await errorMessage(res));
+  return res.json();
 }
 
 export async function deleteNewsletter(id: string): Promise<void> {
   return apiDelete(`/api/me/newsletters/${id}`);
 }
 
+export type Job = {
+  id: string;
+  kind: 'send_sample' | 'scheduled_run';
+  newsletter_config_id: string | null;
+  status: 'queued' | 'running' | 'succeeded' | 'failed';
+  error_message: string | null;
+  created_at: string;
+  started_at: string | null;
+  finished_at: string | null;
+};
+
+export async function getJob(id: string): Promise<Job> {
+  return apiGet<Job>(`/api/jobs/${id}`);
+}
+
 export async function sendNewsletterSample(
   id: string,
   body?: CreateNewsletterBody,
-): Promise<{ sent: boolean }> {
+): Promise<{ job_id: string; status: string }> {
   const headers = await getAuthHeaders();
   const res = await fetch(`${API_BASE}/api/me/newsletters/${id}/send-sample`, {
     method: 'POST',
     headers,
     body: body ? JSON.stringify(body) : undefined,
   });
-  if (!res.ok) throw new Error(await res.text().catch(() => res.statusText));
+  if (!res.ok) throw new Error(await errorMessage(res));
   return res.json();
 }
 
-export type ApprovalStatus = { approved: boolean };
+/** Runs today's issue now (with run log), e.g. to redo a failed one; 409 once it has been sent. */
+export async function runNewsletterNow(id: string): Promise<{ job_id: string; status: string }> {
+  return apiPost<{ job_id: string; status: string }>(`/api/me/newsletters/${id}/run`, {});
+}
+
+/** Upcoming sends across all newsletters over the next `days` (default 7), soonest first. */
+export async function getSchedule(days?: number): Promise<ScheduledSend[]> {
+  return apiGet<ScheduledSend[]>(days ? `/api/me/schedule?days=${days}` : '/api/me/schedule');
+}
+
+export async function getUsage(): Promise<Usage> {
+  return apiGet<Usage>('/api/me/usage');
+}
+
+export type SampleProgressEvent =
+  | { event: 'queued'; job_id: string }
+  | { event: 'generating' | 'sending' }
+  | { event: 'token'; text: string }
+  | { event: 'rendering'; subject: string }
+  | { event: 'done'; sent: boolean }
+  | { event: 'error'; message: string };
+
+/** Sends a sample via the SSE endpoint, calling `onEvent` for each progress event. */
+export async function streamNewsletterSample(
+  id: string,
+  body: CreateNewsletterBody | undefined,
+  onEvent: (e: SampleProgressEvent) => void,
+): Promise<void> {
+  const headers = await getAuthHeaders();
+  const res = await fetch(`${API_BASE}/api/me/newsletters/${id}/send-sample/stream`, {
+    method: 'POST',
+    headers,
+    body: body ? JSON.stringify(body) : undefined,
+  });
+  if (!res.ok || !res.body) throw new Error(await errorMessage(res));
+  const reader = res.body.getReader();
+  const decoder = new TextDecoder();
+  let buf = '';
+  for (;;) {
+    const { done, value } = await reader.read();
+    if (done) break;
+    buf += decoder.decode(value, { stream: true });
+    let end;
+    while ((end = buf.indexOf('\n\n')) >= 0) {
+      const block = buf.slice(0, end);
+      buf = buf.slice(end + 2);
+      let event = 'message';
+      let data = '';
+      for (const line of block.split('\n')) {
+        if (line.startsWith('event:')) event = line.slice(6).trim();
+        else if (line.startsWith('data:')) data += line.slice(5).trim();
+      }
+      if (!data) continue;
+      onEvent({ event, ...JSON.parse(data) } as SampleProgressEvent);
+    }
+  }
+}
+
+export type NewsletterPreview = { subject: string; markdown: string; html: string };
+
+export async function previewNewsletter(id: string, body?: CreateNewsletterBody): Promise<NewsletterPreview> {
+  return apiPost<NewsletterPreview>(`/api/me/newsletters/${id}/preview`, body ?? {});
+}
+
+export async function listSections(): Promise<NewsletterSection[]> {
+  return apiGet<NewsletterSection[]>('/api/sections');
+}
+
+export async function listPresets(): Promise<NewsletterPreset[]> {
+  return apiGet<NewsletterPreset[]>('/api/presets');
+}
+
+export type CreatePresetBody = {
+  name: string;
+  description?: string;
+  topics?: string[];
+  tone?: string;
+  length?: string;
+  features?: Record<string, { enabled: boolean; custom_request: string }>;
+  prompt_template_version?: number | null;
+};
+
+export async function publishPreset(body: CreatePresetBody): Promise<NewsletterPreset> {
+  return apiPost<NewsletterPreset>('/api/me/presets', body);
+}
+
+export async function deletePreset(id: string): Promise<void> {
+  return apiDelete(`/api/me/presets/${id}`);
+}
+
+/** Creates a newsletter from a preset; `overlay` changes it before it is saved. */
+export async function instantiatePreset(
+  id: string,
+  overlay?: CreateNewsletterBody,
+): Promise<NewsletterConfig> {
+  return apiPost<NewsletterConfig>(`/api/presets/${id}/instantiate`, overlay ?? {});
+}
+
+export type ApprovalStatus = {
+  approved: boolean;
+  access_request: 'pending' | 'approved' | 'rejected' | null;
+};
+
+export type AccessRequest = {
+  id: string;
+  status: 'pending' | 'approved' | 'rejected';
+  message: string;
+  created_at: string;
+};
+
+export async function requestAccess(message?: string): Promise<AccessRequest> {
+  return apiPost<AccessRequest>('/api/me/access-request', { message });
+}
 
 export async function getApprovalStatus(): Promise<ApprovalStatus> {
   return apiGet<ApprovalStatus>('/api/me/approval-status');
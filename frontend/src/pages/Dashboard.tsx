@@ -1,10 +1,12 @@
 import { useEffect, useState } from 'react';
-import { Link } from 'react-router-dom';
-import { listNewsletters, deleteNewsletter } from '../lib/api';
-import type { NewsletterConfig } from '../types';
+import { Link, useNavigate } from 'react-router-dom';
+import { listNewsletters, deleteNewsletter, duplicateNewsletter, listPresets, instantiatePreset } from '../lib/api';
+import type { NewsletterConfig, NewsletterPreset } from '../types';
 
 export function Dashboard() {
+  const navigate = useNavigate();
   const [configs, setConfigs] = useState<NewsletterConfig[]>([]);
+  const [presets, setPresets] = useState<NewsletterPreset[]>([]);
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   useEffect(() => {
@@ -12,6 +14,9 @@ export function Dashboard() {
       .then(setConfigs)
       .catch((e) => setError(e instanceof Error ? e.message : 'Failed to load'))
       .finally(() => setLoading(false));
+    listPresets()
+      .then(setPresets)
+      .catch(() => setPresets([]));
   }, []);
 
   const handleDelete = async (id: string, e: React.MouseEvent) => {
@@ -25,6 +30,25 @@ export function Dashboard() {
     }
   };
 
+  const handleDuplicate = async (id: string, e: React.MouseEvent) => {
+    e.preventDefault();
+    try {
+      const copy = await duplicateNewsletter(id);
+      setConfigs((prev) => [copy, ...prev]);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Duplicate failed');
+    }
+  };
+
+  const handleUsePreset = async (id: string) => {
+    try {
+      const created = await instantiatePreset(id);
+      navigate(`/newsletters/${created.id}/edit`);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Could not create from preset');
+    }
+  };
+
   if (loading) return <p>Loading…</p>;
   if (error) return <p className="error">{error}</p>;
 
@@ -45,7 +69,18 @@ export function Dashboard() {
                 <span className={`status-badge ${c.is_active ? 'status-active' : 'status-paused'}`}>
                   {c.is_active ? 'Active' : 'Paused'}
                 </span>
+                {c.next_run_at && (
+                  <span className="next-run">Next: {new Date(c.next_run_at).toLocaleString()}</span>
+                )}
               </Link>
+              <button
+                type="button"
+                onClick={(e) => handleDuplicate(c.id, e)}
+                className="duplicate-btn"
+                aria-label="Duplicate"
+              >
+                Duplicate
+              </button>
               <button
                 type="button"
                 onClick={(e) => handleDelete(c.id, e)}
@@ -58,6 +93,23 @@ export function Dashboard() {
           ))}
         </ul>
       )}
+      {presets.length > 0 && (
+        <section className="preset-gallery">
+          <h2>Start from a preset</h2>
+          <ul className="preset-list">
+            {presets.map((p) => (
+              <li key={p.id} className="preset-item">
+                <strong>{p.name}</strong>
+                {p.curated && <span className="preset-badge">Curated</span>}
+                {p.description && <p>{p.description}</p>}
+                <button type="button" onClick={() => handleUsePreset(p.id)}>
+                  Use preset
+                </button>
+              </li>
+            ))}
+          </ul>
+        </section>
+      )}
     </div>
   );
 }
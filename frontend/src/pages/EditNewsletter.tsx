@@ -4,33 +4,16 @@ import {
   getNewsletter,
   createNewsletter,
   updateNewsletter,
-  sendNewsletterSample,
+  streamNewsletterSample,
+  runNewsletterNow,
+  listSections,
   type CreateNewsletterBody,
 } from '../lib/api';
 import { supabase } from '../supabaseClient';
-import { DEFAULT_FEATURES } from '../types';
+import { DEFAULT_FEATURES, type NewsletterSection } from '../types';
 
 // const TONES = ['neutral', 'playful', 'serious', 'professional'];
 // const LENGTHS = ['short', 'medium', 'long'];
-const FEATURE_KEYS = ['kpis', 'competitor_analysis', 'market_segment_summary', 'identify_risks'] as const;
-const FEATURE_LABELS: Record<string, string> = {
-  kpis: 'KPIs',
-  competitor_analysis: 'Competitor analysis',
-  market_segment_summary: 'Market segment summary',
-  identify_risks: 'Identify risks',
-};
-const FEATURE_DESCRIPTIONS: Record<string, string> = {
-  kpis: 'Key performance indicators: metrics, trends, and how they compare to targets or prior period.',
-  competitor_analysis: 'Summary of what competitors are doing and how they compare.',
-  market_segment_summary: 'Overview of market segments, size, and how they are changing.',
-  identify_risks: 'Risks and uncertainties that could affect your business or market.',
-};
-const FEATURE_PLACEHOLDERS: Record<string, string> = {
-  kpis: 'e.g. Revenue, conversion, churn; compare to last quarter and highlight outliers.',
-  competitor_analysis: 'e.g. Focus on pricing moves and who\'s gaining share in EMEA.',
-  market_segment_summary: 'e.g. Break out by enterprise vs SMB and call out growth rates.',
-  identify_risks: 'e.g. Include regulatory and supply chain, rank by likelihood.',
-};
 
 export function EditNewsletter() {
   const { id } = useParams();
@@ -39,6 +22,10 @@ export function EditNewsletter() {
   const [saving, setSaving] = useState(false);
   const [sendingSample, setSendingSample] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [sections, setSections] = useState<NewsletterSection[]>([]);
+  const [sampleStage, setSampleStage] = useState<string | null>(null);
+  const [sampleOutput, setSampleOutput] = useState('');
+  const [loadedUpdatedAt, setLoadedUpdatedAt] = useState<string | undefined>();
   const [form, setForm] = useState<CreateNewsletterBody>({
     title: '',
     topics: [],
@@ -51,6 +38,12 @@ export function EditNewsletter() {
     features: { ...DEFAULT_FEATURES },
   });
 
+  useEffect(() => {
+    listSections()
+      .then(setSections)
+      .catch((e) => setError(e instanceof Error ? e.message : 'Failed to load sections'));
+  }, []);
+
   useEffect(() => {
     if (!id) {
       setLoading(false);
@@ -63,6 +56,7 @@ export function EditNewsletter() {
     }
     getNewsletter(id)
       .then((c) => {
+        setLoadedUpdatedAt(c.updated_at);
         setForm({
           title: c.title,
           topics: c.topics ?? [],
@@ -109,7 +103,7 @@ export function EditNewsletter() {
     setSaving(true);
     try {
       if (id) {
-        await updateNewsletter(id, form);
+        await updateNewsletter(id, form, loadedUpdatedAt);
       } else {
         await createNewsletter(form);
       }
@@ -125,14 +119,34 @@ export function EditNewsletter() {
     if (!id) return;
     setError(null);
     setSendingSample(true);
+    setSampleOutput('');
     try {
-      await sendNewsletterSample(id, form);
+      let failure: string | null = null;
+      await streamNewsletterSample(id, form, (e) => {
+        if (e.event === 'token') setSampleOutput((prev) => prev + e.text);
+        else if (e.event === 'error') failure = e.message;
+        else setSampleStage(e.event);
+      });
+      if (failure) throw new Error(failure);
       setError(null);
       alert('Sample sent to ' + (form.delivery_email || 'your delivery email') + '.');
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Send sample failed');
     } finally {
       setSendingSample(false);
+      setSampleStage(null);
+    }
+  };
+
+  const handleRunNow = async () => {
+    if (!id) return;
+    if (!confirm("Send today's issue now? It will not be sent again at the scheduled time today.")) return;
+    setError(null);
+    try {
+      await runNewsletterNow(id);
+      alert("Today's issue is on its way to " + (form.delivery_email || 'your delivery email') + '.');
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Run failed');
     }
   };
 
@@ -183,7 +197,7 @@ export function EditNewsletter() {
           <p className="features-intro">
             Choose which sections to include and add optional custom instructions for OpenClaw.
           </p>
-          {FEATURE_KEYS.map((key) => {
+          {sections.map(({ key, name, description, default_instructions }) => {
             const enabled = form.features?.[key]?.enabled ?? false;
             return (
               <div key={key} className="feature-row">
@@ -195,14 +209,14 @@ export function EditNewsletter() {
                     onChange={(e) => updateFeature(key, e.target.checked)}
                   />
                   <span className="toggle-switch" aria-hidden />
-                  <span className="feature-name">{FEATURE_LABELS[key]}</span>
+                  <span className="feature-name">{name}</span>
                 </label>
                 <div className="feature-request-block">
                   <p className="feature-description">
-                    {FEATURE_DESCRIPTIONS[key]}
+                    {description}
                   </p>
                   <textarea
-                    placeholder={FEATURE_PLACEHOLDERS[key]}
+                    placeholder={default_instructions ? `e.g. ${default_instructions}` : undefined}
                     value={form.features?.[key]?.custom_request ?? ''}
                     onChange={(e) => updateFeature(key, undefined, e.target.value)}
                     className="feature-request"
@@ -226,7 +240,12 @@ export function EditNewsletter() {
               onClick={handleSendSample}
               disabled={saving || sendingSample}
             >
-              {sendingSample ? 'Sending…' : 'Send a sample now'}
+              {sendingSample ? `${sampleStage ?? 'Sending'}…` : 'Send a sample now'}
+            </button>
+          )}
+          {id && (
+            <button type="button" className="run-now" onClick={handleRunNow} disabled={saving || sendingSample}>
+              Send today's issue now
             </button>
           )}
           <button type="button" onClick={() => navigate('/dashboard')}>
@@ -234,6 +253,7 @@ export function EditNewsletter() {
           </button>
         </div>
       </form>
+      {sampleOutput && <pre className="sample-output">{sampleOutput}</pre>}
     </div>
   );
 }
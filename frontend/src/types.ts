@@ -8,13 +8,87 @@ export type NewsletterConfig = {
   user_id: string;
   title: string;
   topics: string[];
+  tags: string[];
   tone: string;
   length: string;
   send_time_utc: string;
   timezone: string;
   delivery_email: string;
   is_active: boolean;
+  paused_until: string | null;
+  blackouts: Blackout[];
   features: Record<string, FeatureConfig>;
+  prompt_template_version: number | null;
+  /** When the next issue goes out; null if inactive or every coming day is skipped. */
+  next_run_at: string | null;
+  last_run: LastRun | null;
+  created_at: string;
+  updated_at: string;
+};
+
+export type LastRun = {
+  status: 'success' | 'failure' | 'skipped';
+  run_at: string;
+  error_message: string | null;
+};
+
+/** One upcoming send from `GET /api/me/schedule`. */
+export type ScheduledSend = {
+  newsletter_id: string;
+  title: string;
+  delivery_email: string;
+  run_at: string;
+};
+
+/** A sliding-window limit; `resets_in_seconds` is when the oldest counted use drops out. */
+export type RateUsage = {
+  used: number;
+  limit: number;
+  window_seconds: number;
+  resets_in_seconds: number | null;
+};
+
+/** `GET /api/me/usage`. */
+export type Usage = {
+  newsletters: { used: number; limit: number };
+  samples: RateUsage;
+  previews: RateUsage;
+  generations: RateUsage;
+};
+
+/** Days (UTC, `YYYY-MM-DD`, end inclusive) on which scheduled issues are skipped. */
+export type Blackout = {
+  start: string;
+  end: string;
+  repeat?: 'weekly' | 'yearly';
+  until?: string;
+  reason: string;
+};
+
+export type NewsletterSection = {
+  id: string;
+  key: string;
+  built_in: boolean;
+  name: string;
+  description: string;
+  default_instructions: string;
+  sort_order: number;
+  created_at: string;
+  updated_at: string;
+};
+
+/** Gallery entry; `curated` ones are published by admins, `mine` by the current user. */
+export type NewsletterPreset = {
+  id: string;
+  curated: boolean;
+  mine: boolean;
+  name: string;
+  description: string;
+  topics: string[];
+  tone: string;
+  length: string;
+  features: Record<string, FeatureConfig>;
+  prompt_template_version: number | null;
   created_at: string;
   updated_at: string;
 };